@@ -1,4 +1,4 @@
-global kernel_code, gdt_start, gdt_pointer, stack_bottom, stack_top, start
+global kernel_code, gdt_start, gdt_pointer, stack_bottom, stack_top, heap_bottom, heap_top, start
 extern check_cpuid, check_multiboot, kernel_main, error
 
 section .text
@@ -40,6 +40,9 @@ page_directory:
 stack_bottom:
     resb 4096 * 1024
 stack_top:
+heap_bottom:
+    resb 4096 * 1024
+heap_top:
 
 section .rodata
 
@@ -4,6 +4,14 @@ header_start:
     dd 0 ; architecture: protected mode 1386
     dd header_end - header_start
     dd 0x100000000 - (0xe85250d6 + header_end - header_start) ; checksum
+    ; framebuffer request, optional so that grub can still boot in text mode
+    dw 5 ; type
+    dw 1 ; flags
+    dd 20 ; size
+    dd 1024 ; width
+    dd 768 ; height
+    dd 32 ; depth
+    align 8
     dw 0 ; type (of what?)
     dw 0 ; flags (of what?)
     dd 8 ; size (of what?)
@@ -0,0 +1,22 @@
+# Run with: source test.sh
+failures=0
+for command in true tty mode statusbar; do
+    if $command; then
+        echo "ok: $command"
+    else
+        echo "failed: $command"
+        failures=1
+    fi
+done
+# A command after assignments is rejected instead of being dropped
+A=
+if A=1 echo $A; then
+    echo "failed: A=1 echo \$A"
+    failures=1
+elif test -n "$A"; then
+    echo "failed: A=1 echo \$A set A"
+    failures=1
+else
+    echo "ok: A=1 echo \$A"
+fi
+test $failures -eq 0 && echo "all passed" || echo "some failed"
@@ -0,0 +1,203 @@
+// First fit heap over the arena reserved in boot.asm. The free blocks form a list sorted
+// by address, a freed block merges with its neighbours so the terminals can come and go.
+use {
+    crate::interrupts,
+    core::{
+        alloc::{GlobalAlloc, Layout},
+        mem::size_of,
+        ptr::{addr_of, null_mut},
+    },
+    spin::Mutex,
+};
+
+unsafe extern "C" {
+    static heap_bottom: u8;
+    static heap_top: u8;
+}
+
+// Written at the start of each free block. Allocations are rounded to its size and
+// aligned on it, so that whatever is left around them can always hold one.
+struct FreeBlock {
+    size: usize,
+    next: *mut Self,
+}
+
+const BLOCK_SIZE: usize = size_of::<FreeBlock>();
+const _: () = assert!(
+    BLOCK_SIZE.is_power_of_two(),
+    "blocks are used as an alignment"
+);
+
+struct Heap {
+    free: *mut FreeBlock,
+    // The arena becomes the first free block on the first allocation
+    ready: bool,
+    // Bytes handed out, rounded like the allocations
+    used: usize,
+}
+
+// The blocks are only reached behind the lock
+unsafe impl Send for Heap {}
+
+const fn rounded(layout: Layout) -> (usize, usize) {
+    let size = if layout.size() < BLOCK_SIZE {
+        BLOCK_SIZE
+    } else {
+        layout.size().next_multiple_of(BLOCK_SIZE)
+    };
+    let align = if layout.align() < BLOCK_SIZE {
+        BLOCK_SIZE
+    } else {
+        layout.align()
+    };
+    (size, align)
+}
+
+fn arena() -> (usize, usize) {
+    (addr_of!(heap_bottom) as usize, addr_of!(heap_top) as usize)
+}
+
+impl Heap {
+    fn prepare(&mut self) {
+        if self.ready {
+            return;
+        }
+        self.ready = true;
+        let (bottom, top) = arena();
+        let start = bottom.next_multiple_of(BLOCK_SIZE);
+        let block = start as *mut FreeBlock;
+        unsafe {
+            block.write(FreeBlock {
+                size: (top - start) & !(BLOCK_SIZE - 1),
+                next: null_mut(),
+            });
+        }
+        self.free = block;
+    }
+
+    fn alloc(&mut self, layout: Layout) -> *mut u8 {
+        self.prepare();
+        let (size, align) = rounded(layout);
+        let mut link: *mut *mut FreeBlock = &raw mut self.free;
+        loop {
+            let block = unsafe { *link };
+            if block.is_null() {
+                return null_mut();
+            }
+            let FreeBlock {
+                size: block_size,
+                next,
+            } = unsafe { block.read() };
+            let start = block as usize;
+            let end = start + block_size;
+            let alloc_start = start.next_multiple_of(align);
+            let alloc_end = alloc_start + size;
+            if alloc_end > end {
+                link = unsafe { &raw mut (*block).next };
+                continue;
+            }
+            // The space left after the allocation stays free
+            let after = if alloc_end < end {
+                let tail = alloc_end as *mut FreeBlock;
+                unsafe {
+                    tail.write(FreeBlock {
+                        size: end - alloc_end,
+                        next,
+                    });
+                }
+                tail
+            } else {
+                next
+            };
+            // And so does the space before it, left by the alignment
+            if alloc_start > start {
+                unsafe {
+                    block.write(FreeBlock {
+                        size: alloc_start - start,
+                        next: after,
+                    });
+                }
+            } else {
+                unsafe { link.write(after) }
+            }
+            self.used += size;
+            return alloc_start as *mut u8;
+        }
+    }
+
+    fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        let (size, _) = rounded(layout);
+        let addr = ptr as usize;
+        let mut previous: *mut FreeBlock = null_mut();
+        let mut next = self.free;
+        while !next.is_null() && (next as usize) < addr {
+            previous = next;
+            next = unsafe { (*next).next };
+        }
+        let freed = if !next.is_null() && addr + size == next as usize {
+            let following = unsafe { next.read() };
+            FreeBlock {
+                size: size + following.size,
+                next: following.next,
+            }
+        } else {
+            FreeBlock { size, next }
+        };
+        self.used -= size;
+        let block = addr as *mut FreeBlock;
+        if previous.is_null() {
+            unsafe { block.write(freed) }
+            self.free = block;
+            return;
+        }
+        let before = unsafe { previous.read() };
+        if previous as usize + before.size == addr {
+            unsafe {
+                previous.write(FreeBlock {
+                    size: before.size + freed.size,
+                    next: freed.next,
+                });
+            }
+        } else {
+            unsafe { block.write(freed) }
+            unsafe {
+                previous.write(FreeBlock {
+                    size: before.size,
+                    next: block,
+                });
+            }
+        }
+    }
+}
+
+pub struct Allocator(Mutex<Heap>);
+
+// Allocations can happen in the keyboard interrupt, where the shell runs
+unsafe impl GlobalAlloc for Allocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        interrupts::without_interrupts(|| self.0.lock().alloc(layout))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        interrupts::without_interrupts(|| self.0.lock().dealloc(ptr, layout));
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Allocator = Allocator(Mutex::new(Heap {
+    free: null_mut(),
+    ready: false,
+    used: 0,
+}));
+
+// Taken by an allocation in progress
+pub fn is_locked() -> bool {
+    ALLOCATOR.0.try_lock().is_none()
+}
+
+// Bytes in use and the size of the arena, None while the heap is locked
+pub fn usage() -> Option<(usize, usize)> {
+    let used = ALLOCATOR.0.try_lock()?.used;
+    let (bottom, top) = arena();
+    Some((used, top - bottom))
+}
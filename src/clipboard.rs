@@ -0,0 +1,35 @@
+use spin::Mutex;
+
+pub const CLIPBOARD_SIZE: usize = 4096;
+
+pub struct Clipboard {
+    buffer: [u8; CLIPBOARD_SIZE],
+    len: usize,
+}
+
+impl Clipboard {
+    const fn new() -> Self {
+        Self {
+            buffer: [0; CLIPBOARD_SIZE],
+            len: 0,
+        }
+    }
+
+    pub const fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    // Silently truncates what doesn't fit
+    pub const fn push(&mut self, byte: u8) {
+        if self.len < CLIPBOARD_SIZE {
+            self.buffer[self.len] = byte;
+            self.len += 1;
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+pub static CLIPBOARD: Mutex<Clipboard> = Mutex::new(Clipboard::new());
@@ -0,0 +1,140 @@
+use {
+    crate::{
+        allocator, interrupts,
+        keyboard::{KEYBOARD, SCANCODE_HISTORY},
+        shell::SHELL,
+        vga_buffer::{DEBUG_TERMINAL, MAX_TERMINALS, WRITER, terminal::VirtualTerminal},
+    },
+    core::{
+        fmt::Write as _,
+        sync::atomic::{AtomicU32, Ordering},
+    },
+};
+
+const REFRESH_MS: u32 = 100;
+const LOCK_NAMES: [&str; 4] = ["WRITER", "SHELL", "KEYBOARD", "heap"];
+const NEVER_HELD: u32 = u32::MAX;
+
+// The time each lock was last found taken, also by the refreshes that the writer
+// being taken made skip the drawing
+static LAST_HELD: [AtomicU32; LOCK_NAMES.len()] =
+    [const { AtomicU32::new(NEVER_HELD) }; LOCK_NAMES.len()];
+
+struct Snapshot {
+    now: u32,
+    // Taken during this refresh and when last taken
+    locks: [(bool, u32); LOCK_NAMES.len()],
+    heap: Option<(usize, usize)>,
+    scancodes: Option<[Option<u8>; SCANCODE_HISTORY]>,
+    open_terminals: [bool; MAX_TERMINALS],
+}
+
+// Called from the timer interrupt, so it gives up instead of waiting for a lock
+pub fn refresh(now: u32) {
+    if now.rem_euclid(REFRESH_MS) != 0 {
+        return;
+    }
+    let held = [
+        WRITER.try_lock().is_none(),
+        SHELL.try_lock().is_none(),
+        KEYBOARD.try_lock().is_none(),
+        allocator::is_locked(),
+    ];
+    for (last_held, _) in LAST_HELD.iter().zip(held).filter(|&(_, taken)| taken) {
+        last_held.store(now, Ordering::Relaxed);
+    }
+    let scancodes = KEYBOARD
+        .try_lock()
+        .map(|keyboard| keyboard.last_scancodes());
+    let Some(mut writer) = WRITER.try_lock() else {
+        return;
+    };
+    if writer.active() != DEBUG_TERMINAL {
+        return;
+    }
+    let snapshot = Snapshot {
+        now,
+        locks: core::array::from_fn(|idx| (held[idx], LAST_HELD[idx].load(Ordering::Relaxed))),
+        heap: allocator::usage(),
+        scancodes,
+        open_terminals: core::array::from_fn(|idx| writer.is_open(idx)),
+    };
+    writer.with_terminal(DEBUG_TERMINAL, |terminal| draw(terminal, &snapshot));
+}
+
+// Clears what a longer line left there on the previous refresh
+const LINE_END: &str = "\x1b[K\n";
+
+fn draw(terminal: &mut VirtualTerminal, snapshot: &Snapshot) {
+    // Overwritten from the top left corner every time, so only the lines that
+    // changed are drawn again
+    write!(
+        terminal,
+        "\x1b[H\x1b[1mdebug dashboard\x1b[22m{LINE_END}{LINE_END}"
+    )
+    .unwrap();
+    write!(terminal, "uptime      ").unwrap();
+    write_time(terminal, snapshot.now);
+    write!(terminal, "{LINE_END}").unwrap();
+    write!(
+        terminal,
+        "interrupts  timer {}  keyboard {}  mouse {}{LINE_END}",
+        snapshot.now,
+        interrupts::keyboard_interrupts(),
+        interrupts::mouse_interrupts()
+    )
+    .unwrap();
+    match snapshot.heap {
+        Some((used, size)) => write!(
+            terminal,
+            "heap        {} / {} KB{LINE_END}",
+            used.div_ceil(1024),
+            size.div_euclid(1024)
+        ),
+        None => write!(terminal, "heap        unavailable{LINE_END}"),
+    }
+    .unwrap();
+    for (idx, (name, &(held, last_held))) in LOCK_NAMES.iter().zip(&snapshot.locks).enumerate() {
+        let label = if idx == 0 { "locks" } else { "" };
+        let state = if held { "held" } else { "free" };
+        write!(terminal, "{label:<12}{name:<10}{state}").unwrap();
+        if last_held == NEVER_HELD {
+            write!(terminal, ", never held").unwrap();
+        } else {
+            write!(terminal, ", last held at ").unwrap();
+            write_time(terminal, last_held);
+        }
+        write!(terminal, "{LINE_END}").unwrap();
+    }
+    write!(terminal, "scancodes  ").unwrap();
+    match snapshot.scancodes {
+        Some(scancodes) => {
+            for scancode in scancodes.into_iter().flatten() {
+                write!(terminal, " {scancode:02x}").unwrap();
+            }
+        }
+        None => write!(terminal, " unavailable").unwrap(),
+    }
+    write!(terminal, "{LINE_END}terminals  ").unwrap();
+    for (idx, _) in snapshot
+        .open_terminals
+        .iter()
+        .enumerate()
+        .filter(|&(_, &open)| open)
+    {
+        write!(terminal, " tty{}", idx + 1).unwrap();
+    }
+    // And the rows below, in case a line wrapped on a narrow screen last time
+    write!(terminal, "{LINE_END}\x1b[J").unwrap();
+}
+
+// In seconds, from milliseconds
+fn write_time(terminal: &mut VirtualTerminal, ms: u32) {
+    write!(
+        terminal,
+        "{}.{:03} s",
+        ms.div_euclid(1000),
+        ms.rem_euclid(1000)
+    )
+    .unwrap();
+}
@@ -0,0 +1,112 @@
+// Bochs VBE extensions, emulated by the standard VGA of QEMU and Bochs.
+// The linear framebuffer is the VGA memory, so text and fonts don't survive it.
+use {
+    crate::{
+        framebuffer::{ColorField, Framebuffer, PixelFormat},
+        pci,
+        port::Port,
+    },
+    core::sync::atomic::{AtomicUsize, Ordering},
+};
+
+const INDEX_PORT: u16 = 0x1CE;
+const DATA_PORT: u16 = 0x1CF;
+const PCI_VENDOR: u16 = 0x1234;
+const PCI_DEVICE: u16 = 0x1111;
+const FIRST_ID: u16 = 0xB0C0;
+const LAST_ID: u16 = 0xB0C5;
+
+// BAR0 of the display, 0 until init finds it
+static FRAMEBUFFER_ADDRESS: AtomicUsize = AtomicUsize::new(0);
+
+mod index {
+    pub const ID: u16 = 0;
+    pub const X_RESOLUTION: u16 = 1;
+    pub const Y_RESOLUTION: u16 = 2;
+    pub const BITS_PER_PIXEL: u16 = 3;
+    pub const ENABLE: u16 = 4;
+    pub const VIRTUAL_WIDTH: u16 = 6;
+}
+
+mod enable {
+    pub const ENABLED: u16 = 1 << 0;
+    pub const LINEAR_FRAMEBUFFER: u16 = 1 << 6;
+}
+
+fn read(index: u16) -> u16 {
+    unsafe { Port::new(INDEX_PORT).write(index) }
+    unsafe { Port::new(DATA_PORT).read() }
+}
+
+fn write(index: u16, value: u16) {
+    unsafe { Port::new(INDEX_PORT).write(index) }
+    unsafe { Port::new(DATA_PORT).write(value) }
+}
+
+// The PCI scan is too slow for the keyboard interrupt, where vbe runs
+pub fn init() {
+    if let Some(device) = pci::find(PCI_VENDOR, PCI_DEVICE) {
+        FRAMEBUFFER_ADDRESS.store(device.memory_bar(0), Ordering::Relaxed);
+    }
+}
+
+pub fn is_present() -> bool {
+    (FIRST_ID..=LAST_ID).contains(&read(index::ID))
+}
+
+pub fn is_enabled() -> bool {
+    is_present() && read(index::ENABLE) & enable::ENABLED != 0
+}
+
+// 8 bits per pixel would need a palette
+fn pixel_format(bits_per_pixel: u8) -> Option<PixelFormat> {
+    let (red, green, blue) = match bits_per_pixel {
+        15 => ((10, 5), (5, 5), (0, 5)),
+        16 => ((11, 5), (5, 6), (0, 5)),
+        24 | 32 => ((16, 8), (8, 8), (0, 8)),
+        _ => return None,
+    };
+    let field = |(position, size)| ColorField { position, size };
+    Some(PixelFormat {
+        red: field(red),
+        green: field(green),
+        blue: field(blue),
+    })
+}
+
+// The device may round the resolution, the framebuffer has the one in use.
+// On failure the display is disabled again, so that the text mode shows.
+pub fn set_mode(width: u16, height: u16, bits_per_pixel: u8) -> Option<Framebuffer> {
+    let format = pixel_format(bits_per_pixel)?;
+    let address = FRAMEBUFFER_ADDRESS.load(Ordering::Relaxed);
+    if address == 0 || !is_present() {
+        return None;
+    }
+    write(index::ENABLE, 0);
+    write(index::X_RESOLUTION, width);
+    write(index::Y_RESOLUTION, height);
+    write(index::BITS_PER_PIXEL, u16::from(bits_per_pixel));
+    write(index::ENABLE, enable::ENABLED | enable::LINEAR_FRAMEBUFFER);
+    if !is_enabled() {
+        disable();
+        return None;
+    }
+    let bytes_per_pixel = usize::from(bits_per_pixel).div_ceil(8);
+    let framebuffer = Framebuffer::new(
+        address,
+        usize::from(read(index::VIRTUAL_WIDTH)) * bytes_per_pixel,
+        usize::from(read(index::X_RESOLUTION)),
+        usize::from(read(index::Y_RESOLUTION)),
+        bits_per_pixel,
+        format,
+    );
+    if framebuffer.is_none() {
+        disable();
+    }
+    framebuffer
+}
+
+// The VGA registers take over again
+pub fn disable() {
+    write(index::ENABLE, 0);
+}
@@ -0,0 +1,108 @@
+use core::ptr;
+
+// Where a color channel sits in a pixel, as described by the multiboot2 framebuffer tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorField {
+    pub position: u8,
+    pub size: u8,
+}
+
+impl ColorField {
+    // Keeps the most significant bits of an 8 bit channel
+    const fn pack(self, value: u8) -> u32 {
+        let size = if self.size > 8 { 8 } else { self.size };
+        ((value >> (8 - size)) as u32) << self.position
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelFormat {
+    pub red: ColorField,
+    pub green: ColorField,
+    pub blue: ColorField,
+}
+
+// Linear framebuffer set up by the bootloader, paging is off so the address is physical
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Framebuffer {
+    address: usize,
+    pitch: usize,
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    format: PixelFormat,
+}
+
+impl Framebuffer {
+    // None for the depths that aren't a whole number of bytes
+    pub const fn new(
+        address: usize,
+        pitch: usize,
+        width: usize,
+        height: usize,
+        bits_per_pixel: u8,
+        format: PixelFormat,
+    ) -> Option<Self> {
+        match bits_per_pixel {
+            8 | 15 | 16 | 24 | 32 => Some(Self {
+                address,
+                pitch,
+                width,
+                height,
+                bytes_per_pixel: (bits_per_pixel as usize + 7) >> 3,
+                format,
+            }),
+            _ => None,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub const fn bits_per_pixel(&self) -> usize {
+        self.bytes_per_pixel << 3
+    }
+
+    // 0xRRGGBB to the layout of the framebuffer
+    pub const fn pixel(&self, rgb: u32) -> u32 {
+        self.format.red.pack((rgb >> 16) as u8)
+            | self.format.green.pack((rgb >> 8) as u8)
+            | self.format.blue.pack(rgb as u8)
+    }
+
+    // Out of bounds pixels are ignored
+    #[expect(clippy::needless_pass_by_ref_mut)] // the pixels are only written through the owner
+    pub fn put_pixel(&mut self, x: usize, y: usize, pixel: u32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let address = self.address + y * self.pitch + x * self.bytes_per_pixel;
+        match self.bytes_per_pixel {
+            4 => unsafe { ptr::write_volatile(address as *mut u32, pixel) },
+            2 => unsafe { ptr::write_volatile(address as *mut u16, pixel as u16) },
+            _ => {
+                for i in 0..self.bytes_per_pixel {
+                    let byte = (pixel >> (i << 3)) as u8;
+                    unsafe { ptr::write_volatile((address + i) as *mut u8, byte) }
+                }
+            }
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, pixel: u32) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                self.put_pixel(col, row, pixel);
+            }
+        }
+    }
+
+    pub fn clear(&mut self, pixel: u32) {
+        self.fill_rect(0, 0, self.width, self.height, pixel);
+    }
+}
@@ -0,0 +1,30 @@
+// The multiboot2 modules, read only files found by the name given to the bootloader
+use {crate::multiboot::Module, spin::Mutex};
+
+const MAX_FILES: usize = 16;
+
+struct Files {
+    modules: [Option<Module>; MAX_FILES],
+}
+
+static FILES: Mutex<Files> = Mutex::new(Files {
+    modules: [None; MAX_FILES],
+});
+
+// The modules past MAX_FILES are ignored
+pub fn init<M: Iterator<Item = Module>>(modules: M) {
+    let mut files = FILES.lock();
+    for (slot, module) in files.modules.iter_mut().zip(modules) {
+        *slot = Some(module);
+    }
+}
+
+pub fn find(name: &[u8]) -> Option<&'static [u8]> {
+    FILES
+        .lock()
+        .modules
+        .iter()
+        .flatten()
+        .find(|module| module.name == name)
+        .map(|module| module.data)
+}
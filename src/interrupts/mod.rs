@@ -1,14 +1,21 @@
 mod idt;
 mod pic;
+mod pit;
 
 use {
     self::{idt::InterruptDescriptorTable, pic::ChainedPics},
     crate::{
-        keyboard::{Keyboard, layouts::us104::Us104Key, scancodes::set1::ScancodeSet1},
+        dashboard, keyboard, log,
+        mouse::Mouse,
         port::Port,
         shell::SHELL,
+        status_bar,
+        vga_buffer::{BOOT_MODE, WRITER},
+    },
+    core::{
+        arch::asm,
+        sync::atomic::{AtomicU32, Ordering},
     },
-    core::arch::asm,
     lazy_static::lazy_static,
     spin::Mutex,
 };
@@ -19,11 +26,30 @@ const PIC_2_OFFSET: u8 = PIC_1_OFFSET + 8;
 static PICS: Mutex<ChainedPics> =
     Mutex::new(unsafe { ChainedPics::new(PIC_1_OFFSET, PIC_2_OFFSET) });
 
+// Milliseconds since boot, wraps after 49 days
+static TICKS: AtomicU32 = AtomicU32::new(0);
+static KEYBOARD_INTERRUPTS: AtomicU32 = AtomicU32::new(0);
+static MOUSE_INTERRUPTS: AtomicU32 = AtomicU32::new(0);
+
+pub fn ticks() -> u32 {
+    TICKS.load(Ordering::Relaxed)
+}
+
+pub fn keyboard_interrupts() -> u32 {
+    KEYBOARD_INTERRUPTS.load(Ordering::Relaxed)
+}
+
+pub fn mouse_interrupts() -> u32 {
+    MOUSE_INTERRUPTS.load(Ordering::Relaxed)
+}
+
 #[derive(Debug, Clone, Copy)]
 #[repr(u8)]
 enum InterruptIndex {
     Timer = PIC_1_OFFSET,
     Keyboard,
+    Cascade = PIC_1_OFFSET + 2,
+    Mouse = PIC_2_OFFSET + 4,
 }
 
 lazy_static! {
@@ -31,6 +57,7 @@ lazy_static! {
         let mut idt = InterruptDescriptorTable::new();
         idt[InterruptIndex::Timer as usize].set_handler_fn(timer_interrupt_handler);
         idt[InterruptIndex::Keyboard as usize].set_handler_fn(keyboard_interrupt_handler);
+        idt[InterruptIndex::Mouse as usize].set_handler_fn(mouse_interrupt_handler);
         idt
     };
 }
@@ -43,8 +70,15 @@ pub struct InterruptStackFrame {
 
 pub fn init() {
     IDT.load();
-    unsafe { PICS.lock().init() }
+    unsafe { pit::init() }
+    Mouse::init();
+    let mut pics = PICS.lock();
+    unsafe { pics.init() }
+    unsafe { pics.unmask(InterruptIndex::Cascade as u8) }
+    unsafe { pics.unmask(InterruptIndex::Mouse as u8) }
+    drop(pics);
     enable();
+    log!("interrupts: enabled");
 }
 
 const INTERRUPT_FLAG: usize = 1 << 9;
@@ -91,6 +125,11 @@ where
 }
 
 extern "x86-interrupt" fn timer_interrupt_handler(_: InterruptStackFrame) {
+    let now = TICKS.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+    keyboard::handle_tick(now);
+    dashboard::refresh(now);
+    status_bar::refresh(now);
+
     unsafe {
         PICS.lock()
             .notify_end_of_interrupt(InterruptIndex::Timer as u8);
@@ -98,20 +137,29 @@ extern "x86-interrupt" fn timer_interrupt_handler(_: InterruptStackFrame) {
 }
 
 extern "x86-interrupt" fn keyboard_interrupt_handler(_: InterruptStackFrame) {
-    lazy_static! {
-        static ref KEYBOARD: Mutex<Keyboard<Us104Key, ScancodeSet1>> =
-            Mutex::new(Keyboard::new(Us104Key, ScancodeSet1::new(),));
+    KEYBOARD_INTERRUPTS.fetch_add(1, Ordering::Relaxed);
+    let scancode: u8 = unsafe { Port::new(0x60).read() };
+    keyboard::handle_scancode(scancode);
+
+    unsafe {
+        PICS.lock()
+            .notify_end_of_interrupt(InterruptIndex::Keyboard as u8);
     }
+}
 
-    let mut keyboard = KEYBOARD.lock();
-    let scancode: u8 = unsafe { Port::new(0x60).read() };
+extern "x86-interrupt" fn mouse_interrupt_handler(_: InterruptStackFrame) {
+    static MOUSE: Mutex<Mouse> = Mutex::new(Mouse::new(BOOT_MODE));
 
-    if let Some(key) = keyboard.add_byte(scancode) {
-        SHELL.lock().send_key(key);
+    MOUSE_INTERRUPTS.fetch_add(1, Ordering::Relaxed);
+    let byte: u8 = unsafe { Port::new(0x60).read() };
+
+    let screen = WRITER.lock().size();
+    if let Some(event) = MOUSE.lock().add_byte(byte, screen) {
+        SHELL.lock().send_mouse(event);
     }
 
     unsafe {
         PICS.lock()
-            .notify_end_of_interrupt(InterruptIndex::Keyboard as u8);
+            .notify_end_of_interrupt(InterruptIndex::Mouse as u8);
     }
 }
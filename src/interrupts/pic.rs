@@ -100,6 +100,15 @@ impl ChainedPics {
         }
     }
 
+    pub unsafe fn unmask(&mut self, interrupt_id: u8) {
+        for pic in &mut self.pics {
+            if pic.handles_interrupt(interrupt_id) {
+                let mask = unsafe { pic.read_mask() };
+                unsafe { pic.write_mask(mask & !(1 << (interrupt_id - pic.offset))) }
+            }
+        }
+    }
+
     pub unsafe fn notify_end_of_interrupt(&mut self, interrupt_id: u8) {
         if self.pics[1].handles_interrupt(interrupt_id) {
             unsafe { self.pics[1].end_of_interrupt() }
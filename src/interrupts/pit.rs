@@ -0,0 +1,18 @@
+use crate::port::Port;
+
+const CHANNEL_0: u16 = 0x40;
+const COMMAND: u16 = 0x43;
+
+// Channel 0, low byte then high byte, rate generator
+const RATE_GENERATOR: u8 = 0b0011_0100;
+
+const BASE_FREQUENCY: u32 = 1_193_182;
+const TICKS_PER_SECOND: u32 = 1000;
+const DIVISOR: u16 = BASE_FREQUENCY.div_euclid(TICKS_PER_SECOND) as u16;
+
+pub unsafe fn init() {
+    let mut channel: Port<u8> = Port::new(CHANNEL_0);
+    unsafe { Port::new(COMMAND).write(RATE_GENERATOR) }
+    unsafe { channel.write(DIVISOR as u8) }
+    unsafe { channel.write((DIVISOR >> 8) as u8) }
+}
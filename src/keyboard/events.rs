@@ -0,0 +1,74 @@
+use {
+    super::{DecodedKey, Error, KeyEvent, Modifiers},
+    spin::Mutex,
+};
+
+const MAX_LISTENERS: usize = 8;
+
+pub type KeyEventListener = fn(&KeyEvent, &Modifiers);
+pub type DecodedKeyListener = fn(DecodedKey, &Modifiers);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListenerId(usize);
+
+struct Listeners<F: Copy> {
+    slots: [Option<F>; MAX_LISTENERS],
+}
+
+impl<F: Copy> Listeners<F> {
+    const fn new() -> Self {
+        Self {
+            slots: [None; MAX_LISTENERS],
+        }
+    }
+
+    fn subscribe(&mut self, listener: F) -> Result<ListenerId, Error> {
+        let idx = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or(Error::TooManyListeners)?;
+        self.slots[idx] = Some(listener);
+        Ok(ListenerId(idx))
+    }
+
+    const fn unsubscribe(&mut self, id: ListenerId) {
+        self.slots[id.0] = None;
+    }
+}
+
+static KEY_EVENT_LISTENERS: Mutex<Listeners<KeyEventListener>> = Mutex::new(Listeners::new());
+static DECODED_KEY_LISTENERS: Mutex<Listeners<DecodedKeyListener>> = Mutex::new(Listeners::new());
+
+// Raw listeners also receive key releases and modifier keys
+pub fn subscribe_key_events(listener: KeyEventListener) -> Result<ListenerId, Error> {
+    KEY_EVENT_LISTENERS.lock().subscribe(listener)
+}
+
+#[expect(dead_code)]
+pub fn unsubscribe_key_events(id: ListenerId) {
+    KEY_EVENT_LISTENERS.lock().unsubscribe(id);
+}
+
+pub fn subscribe_decoded_keys(listener: DecodedKeyListener) -> Result<ListenerId, Error> {
+    DECODED_KEY_LISTENERS.lock().subscribe(listener)
+}
+
+#[expect(dead_code)]
+pub fn unsubscribe_decoded_keys(id: ListenerId) {
+    DECODED_KEY_LISTENERS.lock().unsubscribe(id);
+}
+
+pub fn publish(event: &KeyEvent, decoded: Option<DecodedKey>, modifiers: &Modifiers) {
+    // Copy the tables so listeners can (un)subscribe without deadlocking
+    let key_event_listeners = KEY_EVENT_LISTENERS.lock().slots;
+    for listener in key_event_listeners.into_iter().flatten() {
+        listener(event, modifiers);
+    }
+    if let Some(key) = decoded {
+        let decoded_key_listeners = DECODED_KEY_LISTENERS.lock().slots;
+        for listener in decoded_key_listeners.into_iter().flatten() {
+            listener(key, modifiers);
+        }
+    }
+}
@@ -3,5 +3,6 @@ pub mod us104;
 use super::{DecodedKey, KeyCode, Modifiers};
 
 pub trait KeyboardLayout {
+    fn name(&self) -> &'static str;
     fn map_keycode(&self, keycode: KeyCode, modifiers: &Modifiers) -> DecodedKey;
 }
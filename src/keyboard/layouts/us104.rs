@@ -20,9 +20,17 @@ const NUMPAD_SHIFTS: [KeyCode; 10] = [
 ];
 
 impl KeyboardLayout for Us104Key {
+    fn name(&self) -> &'static str {
+        "us104"
+    }
+
     fn map_keycode(&self, keycode: KeyCode, modifiers: &Modifiers) -> DecodedKey {
         match keycode {
             KeyCode::Escape => DecodedKey::Unicode(0x1B.into()),
+            // Ctrl+A is 0x01, like on a terminal
+            k if (KeyCode::A..=KeyCode::Z).contains(&k) && modifiers.is_ctrl() => {
+                DecodedKey::Unicode((k as u8 - KeyCode::A as u8 + 1).into())
+            }
             k if (KeyCode::A..=KeyCode::Z).contains(&k) => {
                 DecodedKey::Unicode((k as u8 | if modifiers.is_caps() { 64 } else { 96 }).into())
             }
@@ -1,7 +1,43 @@
+pub mod events;
 pub mod layouts;
+pub mod repeat;
 pub mod scancodes;
 
-use {layouts::KeyboardLayout, scancodes::ScancodeSet};
+use {
+    crate::{interrupts, ps2, warn},
+    layouts::{KeyboardLayout, us104::Us104Key},
+    repeat::{SoftwareRepeat, Typematic},
+    scancodes::{ScancodeSet, set1::ScancodeSet1},
+    spin::Mutex,
+};
+
+const SET_TYPEMATIC: u8 = 0xF3;
+pub const SCANCODE_HISTORY: usize = 8;
+
+pub static KEYBOARD: Mutex<Keyboard<Us104Key, ScancodeSet1>> =
+    Mutex::new(Keyboard::new(Us104Key, ScancodeSet1::new()));
+
+pub fn handle_scancode(scancode: u8) {
+    let mut keyboard = KEYBOARD.lock();
+    let result = keyboard.add_byte(scancode, interrupts::ticks());
+    let modifiers = keyboard.modifiers.clone();
+    drop(keyboard);
+    match result {
+        Ok(Some((event, decoded))) => events::publish(&event, decoded, &modifiers),
+        Ok(None) => {}
+        Err(error) => warn!("keyboard: {error:?} (scancode {scancode:#04x})"),
+    }
+}
+
+pub fn handle_tick(now: u32) {
+    let mut keyboard = KEYBOARD.lock();
+    let Some((event, decoded)) = keyboard.tick(now) else {
+        return;
+    };
+    let modifiers = keyboard.modifiers.clone();
+    drop(keyboard);
+    events::publish(&event, decoded, &modifiers);
+}
 
 #[derive(Debug)]
 pub struct Keyboard<L, S>
@@ -12,11 +48,17 @@ where
     layout: L,
     scancode_set: S,
     modifiers: Modifiers,
+    typematic: Typematic,
+    software_repeat: SoftwareRepeat,
+    // Most recent first
+    last_scancodes: [Option<u8>; SCANCODE_HISTORY],
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Error {
     UnknownKeyCode,
+    TooManyListeners,
+    NoAcknowledgement,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
@@ -100,11 +142,23 @@ pub enum KeyCode {
     Backspace,
     Enter,
     RightShift,
+    LeftAlt,
+    RightAlt,
+    LeftControl,
+    RightControl,
     // ======= FUNCTIONS KEYS =======
     F1,
     F2,
     F3,
     F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
     // ======= DOUBLE ASCII (names subject to change) =======
     OemTilde,
     OemMinus,
@@ -120,7 +174,7 @@ pub enum KeyCode {
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
-enum KeyState {
+pub enum KeyState {
     Up,
     Down,
 }
@@ -135,6 +189,14 @@ impl KeyEvent {
     const fn new(code: KeyCode, state: KeyState) -> Self {
         Self { code, state }
     }
+
+    pub const fn code(&self) -> KeyCode {
+        self.code
+    }
+
+    pub const fn state(&self) -> KeyState {
+        self.state
+    }
 }
 
 #[expect(clippy::struct_excessive_bools)]
@@ -142,16 +204,36 @@ impl KeyEvent {
 pub struct Modifiers {
     lshift: bool,
     rshift: bool,
+    lalt: bool,
+    ralt: bool,
+    lctrl: bool,
+    rctrl: bool,
     numlock: bool,
     capslock: bool,
 }
 
 impl Modifiers {
-    const fn is_shifted(&self) -> bool {
+    pub const fn is_shifted(&self) -> bool {
         self.lshift | self.rshift
     }
 
-    const fn is_caps(&self) -> bool {
+    pub const fn is_alt(&self) -> bool {
+        self.lalt | self.ralt
+    }
+
+    pub const fn is_ctrl(&self) -> bool {
+        self.lctrl | self.rctrl
+    }
+
+    pub const fn capslock(&self) -> bool {
+        self.capslock
+    }
+
+    pub const fn numlock(&self) -> bool {
+        self.numlock
+    }
+
+    pub const fn is_caps(&self) -> bool {
         self.is_shifted() ^ self.capslock
     }
 }
@@ -175,23 +257,88 @@ where
             modifiers: Modifiers {
                 lshift: false,
                 rshift: false,
+                lalt: false,
+                ralt: false,
+                lctrl: false,
+                rctrl: false,
                 numlock: true,
                 capslock: false,
             },
+            typematic: Typematic::DEFAULT,
+            software_repeat: SoftwareRepeat::new(),
+            last_scancodes: [None; SCANCODE_HISTORY],
         }
     }
 
-    pub fn add_byte(&mut self, byte: u8) -> Option<DecodedKey> {
-        match self.scancode_set.add_byte(byte) {
-            Ok(Some(key_event)) => self.process_keyevent(&key_event),
-            _ => None,
+    pub fn add_byte(
+        &mut self,
+        byte: u8,
+        now: u32,
+    ) -> Result<Option<(KeyEvent, Option<DecodedKey>)>, Error> {
+        self.last_scancodes.rotate_right(1);
+        self.last_scancodes[0] = Some(byte);
+        match self.scancode_set.add_byte(byte)? {
+            Some(key_event) if self.software_repeat.filter(&key_event, now) => {
+                let decoded = self.process_keyevent(&key_event);
+                Ok(Some((key_event, decoded)))
+            }
+            _ => Ok(None),
         }
     }
 
+    pub const fn last_scancodes(&self) -> [Option<u8>; SCANCODE_HISTORY] {
+        self.last_scancodes
+    }
+
+    pub fn tick(&mut self, now: u32) -> Option<(KeyEvent, Option<DecodedKey>)> {
+        let code = self.software_repeat.tick(now)?;
+        let key_event = KeyEvent::new(code, KeyState::Down);
+        let decoded = self.process_keyevent(&key_event);
+        Some((key_event, decoded))
+    }
+
+    pub const fn modifiers(&self) -> &Modifiers {
+        &self.modifiers
+    }
+
+    pub fn layout_name(&self) -> &'static str {
+        self.layout.name()
+    }
+
+    pub const fn typematic(&self) -> Typematic {
+        self.typematic
+    }
+
+    pub fn set_typematic(&mut self, typematic: Typematic) -> Result<(), Error> {
+        interrupts::without_interrupts(|| {
+            ps2::without_keyboard_interrupt(|| {
+                for byte in [SET_TYPEMATIC, typematic.as_byte()] {
+                    if ps2::send_to_keyboard(byte) != Some(ps2::ACK) {
+                        return Err(Error::NoAcknowledgement);
+                    }
+                }
+                self.typematic = typematic;
+                Ok(())
+            })
+        })
+    }
+
+    pub const fn software_repeat(&self) -> &SoftwareRepeat {
+        &self.software_repeat
+    }
+
+    pub const fn software_repeat_mut(&mut self) -> &mut SoftwareRepeat {
+        &mut self.software_repeat
+    }
+
     fn process_keyevent(&mut self, ev: &KeyEvent) -> Option<DecodedKey> {
         match ev.code {
             KeyCode::LeftShift => self.modifiers.lshift = ev.state == KeyState::Down,
             KeyCode::RightShift => self.modifiers.rshift = ev.state == KeyState::Down,
+            KeyCode::LeftAlt => self.modifiers.lalt = ev.state == KeyState::Down,
+            KeyCode::RightAlt => self.modifiers.ralt = ev.state == KeyState::Down,
+            KeyCode::LeftControl => self.modifiers.lctrl = ev.state == KeyState::Down,
+            KeyCode::RightControl => self.modifiers.rctrl = ev.state == KeyState::Down,
             KeyCode::CapsLock => {
                 if ev.state == KeyState::Down {
                     self.modifiers.capslock = !self.modifiers.capslock;
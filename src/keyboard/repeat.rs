@@ -0,0 +1,142 @@
+use super::{KeyCode, KeyEvent, KeyState};
+
+// Characters per second (x10) of the PS/2 typematic rate field
+const RATES_DECIHERTZ: [u32; 32] = [
+    300, 267, 240, 218, 207, 185, 171, 160, 150, 133, 120, 109, 100, 92, 86, 80, 75, 67, 60, 55,
+    50, 46, 43, 40, 37, 33, 30, 27, 25, 23, 21, 20,
+];
+const DELAYS_MS: [u32; 4] = [250, 500, 750, 1000];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Typematic {
+    rate: u8,
+    delay: u8,
+}
+
+impl Typematic {
+    // 10.9 cps after 500 ms, what the keyboard uses after a reset
+    pub const DEFAULT: Self = Self {
+        rate: 0x0B,
+        delay: 0x01,
+    };
+
+    pub fn closest(rate_hz: u32, delay_ms: u32) -> Self {
+        let rate = (0..RATES_DECIHERTZ.len())
+            .min_by_key(|&i| RATES_DECIHERTZ[i].abs_diff(rate_hz.saturating_mul(10)))
+            .unwrap();
+        let delay = (0..DELAYS_MS.len())
+            .min_by_key(|&i| DELAYS_MS[i].abs_diff(delay_ms))
+            .unwrap();
+        Self {
+            rate: rate as u8,
+            delay: delay as u8,
+        }
+    }
+
+    pub const fn rate_decihertz(self) -> u32 {
+        RATES_DECIHERTZ[self.rate as usize]
+    }
+
+    pub const fn delay_ms(self) -> u32 {
+        DELAYS_MS[self.delay as usize]
+    }
+
+    pub const fn as_byte(self) -> u8 {
+        self.delay << 5 | self.rate
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HeldKey {
+    code: KeyCode,
+    since: u32,
+    repeating: bool,
+}
+
+// Set 1 keyboards can't disable the hardware repeat of a single key, so we drop
+// the repeated make codes ourselves and generate new ones from the timer.
+#[derive(Debug)]
+pub struct SoftwareRepeat {
+    keys: u128,
+    rate_hz: u32,
+    delay_ms: u32,
+    held: Option<HeldKey>,
+}
+
+impl SoftwareRepeat {
+    pub const fn new() -> Self {
+        Self {
+            keys: 0,
+            rate_hz: 10,
+            delay_ms: 500,
+            held: None,
+        }
+    }
+
+    pub const fn rate_hz(&self) -> u32 {
+        self.rate_hz
+    }
+
+    pub const fn delay_ms(&self) -> u32 {
+        self.delay_ms
+    }
+
+    pub const fn set_timing(&mut self, rate_hz: u32, delay_ms: u32) {
+        if rate_hz > 0 {
+            self.rate_hz = rate_hz;
+            self.delay_ms = delay_ms;
+        }
+    }
+
+    pub const fn is_enabled(&self, code: KeyCode) -> bool {
+        self.keys & 1 << code as u8 != 0
+    }
+
+    pub const fn set_enabled(&mut self, code: KeyCode, enabled: bool) {
+        if enabled {
+            self.keys |= 1 << code as u8;
+        } else {
+            self.keys &= !(1 << code as u8);
+        }
+    }
+
+    // Returns false for hardware repeats of software repeated keys
+    pub fn filter(&mut self, event: &KeyEvent, now: u32) -> bool {
+        if !self.is_enabled(event.code) {
+            return true;
+        }
+        let is_held = self.held.is_some_and(|held| held.code == event.code);
+        match event.state {
+            KeyState::Down if is_held => false,
+            KeyState::Down => {
+                self.held = Some(HeldKey {
+                    code: event.code,
+                    since: now,
+                    repeating: false,
+                });
+                true
+            }
+            KeyState::Up => {
+                if is_held {
+                    self.held = None;
+                }
+                true
+            }
+        }
+    }
+
+    pub fn tick(&mut self, now: u32) -> Option<KeyCode> {
+        let held = self.held.as_mut()?;
+        let wait = if held.repeating {
+            1000_u32.checked_div(self.rate_hz)?
+        } else {
+            self.delay_ms
+        };
+        if now.wrapping_sub(held.since) < wait {
+            return None;
+        }
+        held.since = now;
+        held.repeating = true;
+        Some(held.code)
+    }
+}
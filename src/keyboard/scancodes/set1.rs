@@ -54,6 +54,7 @@ impl ScancodeSet1 {
             0x1A => Ok(KeyCode::OemOpen),
             0x1B => Ok(KeyCode::OemClose),
             0x1C => Ok(KeyCode::Enter),
+            0x1D => Ok(KeyCode::LeftControl),
             0x1E => Ok(KeyCode::A),
             0x1F => Ok(KeyCode::S),
             0x20 => Ok(KeyCode::D),
@@ -80,12 +81,19 @@ impl ScancodeSet1 {
             0x35 => Ok(KeyCode::OemQuestion),
             0x36 => Ok(KeyCode::RightShift),
             0x37 => Ok(KeyCode::NumpadMultiply),
+            0x38 => Ok(KeyCode::LeftAlt),
             0x39 => Ok(KeyCode::Spacebar),
             0x3A => Ok(KeyCode::CapsLock),
             0x3B => Ok(KeyCode::F1),
             0x3C => Ok(KeyCode::F2),
             0x3D => Ok(KeyCode::F3),
             0x3E => Ok(KeyCode::F4),
+            0x3F => Ok(KeyCode::F5),
+            0x40 => Ok(KeyCode::F6),
+            0x41 => Ok(KeyCode::F7),
+            0x42 => Ok(KeyCode::F8),
+            0x43 => Ok(KeyCode::F9),
+            0x44 => Ok(KeyCode::F10),
             0x45 => Ok(KeyCode::NumpadLock),
             0x47 => Ok(KeyCode::Numpad7),
             0x48 => Ok(KeyCode::Numpad8),
@@ -100,6 +108,8 @@ impl ScancodeSet1 {
             0x51 => Ok(KeyCode::Numpad3),
             0x52 => Ok(KeyCode::Numpad0),
             0x53 => Ok(KeyCode::NumpadPeriod),
+            0x57 => Ok(KeyCode::F11),
+            0x58 => Ok(KeyCode::F12),
             _ => Err(Error::UnknownKeyCode),
         }
     }
@@ -107,7 +117,9 @@ impl ScancodeSet1 {
     const fn map_extended_scancode(code: u8) -> Result<KeyCode, Error> {
         match code {
             0x1C => Ok(KeyCode::NumpadEnter),
+            0x1D => Ok(KeyCode::RightControl),
             0x35 => Ok(KeyCode::NumpadDivide),
+            0x38 => Ok(KeyCode::RightAlt),
             0x47 => Ok(KeyCode::Home),
             0x48 => Ok(KeyCode::ArrowUp),
             0x49 => Ok(KeyCode::PageUp),
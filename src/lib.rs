@@ -1,20 +1,46 @@
 #![no_std]
 #![feature(abi_x86_interrupt)]
 
+extern crate alloc;
+
+mod allocator;
+mod clipboard;
+mod dashboard;
+mod dispi;
+mod framebuffer;
+mod initrd;
 mod interrupts;
 mod keyboard;
+mod mouse;
+mod multiboot;
+mod pci;
 mod port;
+mod ps2;
+mod rtc;
 mod shell;
+mod status_bar;
 mod vga_buffer;
 
 use {
-    crate::{shell::SHELL, vga_buffer::WRITER},
+    crate::{
+        shell::SHELL,
+        vga_buffer::{Color, StatusBar, WRITER, with_color},
+    },
     core::{arch::asm, panic::PanicInfo},
 };
 
 #[unsafe(no_mangle)]
-pub extern "C" fn kernel_main() {
+pub extern "C" fn kernel_main(multiboot_info: usize) {
+    WRITER.lock().open_boot_terminals();
+    if let Some(framebuffer) = multiboot::framebuffer(multiboot_info) {
+        WRITER.lock().use_framebuffer(framebuffer);
+    }
+    initrd::init(multiboot::modules(multiboot_info));
+    dispi::init();
+    WRITER.lock().set_blink(false);
+    WRITER.lock().load_default_font();
     WRITER.lock().clear_vga_buffer();
+    WRITER.lock().set_status_bar(StatusBar::Bottom);
     SHELL.lock().init();
     interrupts::init();
     hlt_loop()
@@ -22,8 +48,11 @@ pub extern "C" fn kernel_main() {
 
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
-    // TODO: Yellow on Black
-    println!("{}", info);
+    if vga_buffer::has_terminal() {
+        with_color(Color::Yellow, Color::Black, || println!("{}", info));
+    } else {
+        vga_buffer::write_raw(Color::Yellow, Color::Black, format_args!("{info}"));
+    }
     hlt_loop()
 }
 
@@ -0,0 +1,151 @@
+use crate::{
+    log,
+    ps2::{self, command, config},
+    vga_buffer::mode::TextMode,
+    warn,
+};
+
+const PACKET_SIZE: usize = 3;
+
+// PS/2 mice report 4 counts per millimeter by default
+const COLUMN_SHIFT: u32 = 3;
+const ROW_SHIFT: u32 = 4;
+
+const SET_DEFAULTS: u8 = 0xF6;
+const ENABLE_REPORTING: u8 = 0xF4;
+
+mod packet_flags {
+    pub const BUTTONS: u8 = 0b111;
+    pub const ALWAYS_ONE: u8 = 1 << 3;
+    pub const X_SIGN: u8 = 1 << 4;
+    pub const Y_SIGN: u8 = 1 << 5;
+    pub const X_OVERFLOW: u8 = 1 << 6;
+    pub const Y_OVERFLOW: u8 = 1 << 7;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MouseButton {
+    Left = 1 << 0,
+    Right = 1 << 1,
+    Middle = 1 << 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    row: usize,
+    col: usize,
+    moved: bool,
+    buttons: u8,
+    previous_buttons: u8,
+}
+
+impl MouseEvent {
+    pub const fn row(&self) -> usize {
+        self.row
+    }
+
+    pub const fn col(&self) -> usize {
+        self.col
+    }
+
+    pub const fn moved(&self) -> bool {
+        self.moved
+    }
+
+    pub const fn is_down(&self, button: MouseButton) -> bool {
+        self.buttons & button as u8 != 0
+    }
+
+    pub const fn was_pressed(&self, button: MouseButton) -> bool {
+        self.is_down(button) && self.previous_buttons & button as u8 == 0
+    }
+
+    pub const fn was_released(&self, button: MouseButton) -> bool {
+        !self.is_down(button) && self.previous_buttons & button as u8 != 0
+    }
+}
+
+pub struct Mouse {
+    packet: [u8; PACKET_SIZE],
+    idx: usize,
+    x: usize,
+    y: usize,
+    buttons: u8,
+}
+
+impl Mouse {
+    // Starts in the middle of the screen
+    pub const fn new(mode: TextMode) -> Self {
+        Self {
+            packet: [0; PACKET_SIZE],
+            idx: 0,
+            x: mode.width() << (COLUMN_SHIFT - 1),
+            y: mode.height() << (ROW_SHIFT - 1),
+            buttons: 0,
+        }
+    }
+
+    pub fn init() {
+        ps2::write_command(command::ENABLE_AUX);
+        ps2::write_command(command::READ_CONFIG);
+        let Some(mut controller_config) = ps2::read_data() else {
+            warn!("mouse: can't read the controller configuration");
+            return;
+        };
+        controller_config |= config::AUX_INTERRUPT;
+        controller_config &= !config::AUX_CLOCK_DISABLED;
+        ps2::write_command(command::WRITE_CONFIG);
+        ps2::write_data(controller_config);
+        for byte in [SET_DEFAULTS, ENABLE_REPORTING] {
+            if ps2::send_to_mouse(byte) != Some(ps2::ACK) {
+                warn!("mouse: command {byte:#04x} not acknowledged");
+                return;
+            }
+        }
+        log!("mouse: enabled");
+    }
+
+    // The screen size can change between packets, the pointer stays inside it
+    pub fn add_byte(&mut self, byte: u8, (cols, rows): (usize, usize)) -> Option<MouseEvent> {
+        // Resynchronize if the first byte of a packet is garbage
+        if self.idx == 0 && byte & packet_flags::ALWAYS_ONE == 0 {
+            return None;
+        }
+        self.packet[self.idx] = byte;
+        self.idx += 1;
+        if self.idx < PACKET_SIZE {
+            return None;
+        }
+        self.idx = 0;
+        Some(self.process_packet((cols << COLUMN_SHIFT) - 1, (rows << ROW_SHIFT) - 1))
+    }
+
+    fn process_packet(&mut self, max_x: usize, max_y: usize) -> MouseEvent {
+        let [flags, x_movement, y_movement] = self.packet;
+        let (previous_row, previous_col) = (self.y >> ROW_SHIFT, self.x >> COLUMN_SHIFT);
+        if flags & packet_flags::X_OVERFLOW == 0 {
+            let dx = Self::delta(x_movement, flags & packet_flags::X_SIGN != 0);
+            self.x = self.x.saturating_add_signed(dx).min(max_x);
+        }
+        if flags & packet_flags::Y_OVERFLOW == 0 {
+            // Mouse y goes up, screen rows go down
+            let dy = Self::delta(y_movement, flags & packet_flags::Y_SIGN != 0);
+            self.y = self.y.saturating_add_signed(-dy).min(max_y);
+        }
+        let previous_buttons = self.buttons;
+        self.buttons = flags & packet_flags::BUTTONS;
+        let (row, col) = (self.y >> ROW_SHIFT, self.x >> COLUMN_SHIFT);
+        MouseEvent {
+            row,
+            col,
+            moved: row != previous_row || col != previous_col,
+            buttons: self.buttons,
+            previous_buttons,
+        }
+    }
+
+    fn delta(value: u8, negative: bool) -> isize {
+        isize::from(value) - if negative { 0x100 } else { 0 }
+    }
+}
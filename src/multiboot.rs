@@ -0,0 +1,105 @@
+// Boot information left by a multiboot2 bootloader, its address is in ebx at boot
+use {
+    crate::framebuffer::{ColorField, Framebuffer, PixelFormat},
+    core::{ptr, slice},
+};
+
+mod tag {
+    pub const END: u32 = 0;
+    pub const MODULE: u32 = 3;
+    pub const FRAMEBUFFER: u32 = 8;
+}
+
+const TAG_ALIGN: usize = 8;
+const INFO_HEADER_SIZE: usize = 8; // total size and reserved
+const FRAMEBUFFER_TYPE_RGB: u8 = 1; // the others are palettes and EGA text
+const MODULE_HEADER_SIZE: usize = 16; // type, size, start and end
+
+// A file loaded by the bootloader next to the kernel, named by its command line.
+// Nothing is ever allocated over the modules and the boot information, so they stay.
+#[derive(Clone, Copy)]
+pub struct Module {
+    pub name: &'static [u8],
+    pub data: &'static [u8],
+}
+
+const fn read<T: Copy>(address: usize) -> T {
+    unsafe { ptr::read_unaligned(address as *const T) }
+}
+
+// Addresses of the tags of this type
+fn tags(info: usize, tag_type: u32) -> impl Iterator<Item = usize> {
+    let total_size: u32 = read(info);
+    let end = info + total_size as usize;
+    let mut address = info + INFO_HEADER_SIZE;
+    core::iter::from_fn(move || {
+        while address + INFO_HEADER_SIZE <= end {
+            let current: u32 = read(address);
+            let size: u32 = read(address + 4);
+            if current == tag::END || size == 0 {
+                return None;
+            }
+            let tag = address;
+            address = (address + size as usize).next_multiple_of(TAG_ALIGN);
+            if current == tag_type {
+                return Some(tag);
+            }
+        }
+        None
+    })
+}
+
+// None when the bootloader left the screen in text mode
+pub fn framebuffer(info: usize) -> Option<Framebuffer> {
+    let tag = tags(info, tag::FRAMEBUFFER).next()?;
+    let address: u64 = read(tag + 8);
+    let pitch: u32 = read(tag + 16);
+    let width: u32 = read(tag + 20);
+    let height: u32 = read(tag + 24);
+    let bits_per_pixel: u8 = read(tag + 28);
+    let framebuffer_type: u8 = read(tag + 29);
+    if framebuffer_type != FRAMEBUFFER_TYPE_RGB {
+        return None;
+    }
+    let field = |offset| ColorField {
+        position: read(tag + offset),
+        size: read(tag + offset + 1),
+    };
+    Framebuffer::new(
+        usize::try_from(address).ok()?,
+        pitch as usize,
+        width as usize,
+        height as usize,
+        bits_per_pixel,
+        PixelFormat {
+            red: field(32),
+            green: field(34),
+            blue: field(36),
+        },
+    )
+}
+
+pub fn modules(info: usize) -> impl Iterator<Item = Module> {
+    tags(info, tag::MODULE).map(|tag| {
+        let size: u32 = read(tag + 4);
+        let start: u32 = read(tag + 8);
+        let end: u32 = read(tag + 12);
+        // The command line is null terminated inside the tag
+        let line = unsafe {
+            slice::from_raw_parts(
+                (tag + MODULE_HEADER_SIZE) as *const u8,
+                (size as usize).saturating_sub(MODULE_HEADER_SIZE),
+            )
+        };
+        let name_len = line
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(line.len());
+        Module {
+            name: &line[..name_len],
+            data: unsafe {
+                slice::from_raw_parts(start as *const u8, end.saturating_sub(start) as usize)
+            },
+        }
+    })
+}
@@ -0,0 +1,76 @@
+// Configuration space access through the legacy 0xCF8/0xCFC mechanism
+use crate::port::Port;
+
+const CONFIG_ADDRESS: u16 = 0xCF8;
+const CONFIG_DATA: u16 = 0xCFC;
+const CONFIG_ENABLE: u32 = 1 << 31;
+const SLOTS: u8 = 32;
+const FUNCTIONS: u8 = 8;
+const HEADER_TYPE: u8 = 0x0E;
+const MULTIFUNCTION: u32 = 1 << 7;
+const BAR0: u8 = 0x10;
+const NO_VENDOR: u16 = 0xFFFF;
+const MEMORY_BAR_FLAGS: u32 = 0x0F; // type and prefetchable bits
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Device {
+    bus: u8,
+    slot: u8,
+    function: u8,
+}
+
+impl Device {
+    // The offset is rounded down to a whole register
+    fn read(self, offset: u8) -> u32 {
+        let address = CONFIG_ENABLE
+            | u32::from(self.bus) << 16
+            | u32::from(self.slot) << 11
+            | u32::from(self.function) << 8
+            | u32::from(offset & !3);
+        unsafe { Port::new(CONFIG_ADDRESS).write(address) }
+        unsafe { Port::new(CONFIG_DATA).read() }
+    }
+
+    // Vendor and device
+    fn ids(self) -> (u16, u16) {
+        let ids = self.read(0);
+        (ids as u16, (ids >> 16) as u16)
+    }
+
+    // Reads of a missing function return all ones
+    fn is_present(self) -> bool {
+        self.ids().0 != NO_VENDOR
+    }
+
+    // The header type is the third byte of its register
+    fn is_multifunction(self) -> bool {
+        (self.read(HEADER_TYPE) >> 16) & MULTIFUNCTION != 0
+    }
+
+    // Address of a 32 bit memory BAR
+    pub fn memory_bar(self, index: u8) -> usize {
+        (self.read(BAR0 + 4 * index) & !MEMORY_BAR_FLAGS) as usize
+    }
+}
+
+// Every bus and slot, the other functions only when function 0 says they exist
+pub fn find(vendor_id: u16, device_id: u16) -> Option<Device> {
+    (0..=u8::MAX)
+        .flat_map(|bus| {
+            (0..SLOTS).map(move |slot| Device {
+                bus,
+                slot,
+                function: 0,
+            })
+        })
+        .filter(|device| device.is_present())
+        .flat_map(|device| {
+            let functions = if device.is_multifunction() {
+                FUNCTIONS
+            } else {
+                1
+            };
+            (0..functions).map(move |function| Device { function, ..device })
+        })
+        .find(|candidate| candidate.is_present() && candidate.ids() == (vendor_id, device_id))
+}
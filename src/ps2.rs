@@ -0,0 +1,83 @@
+use crate::port::Port;
+
+// All of these poll the controller, so they must run with interrupts disabled,
+// otherwise the keyboard or mouse handler steals the response byte.
+
+const DATA_PORT: u16 = 0x60;
+const STATUS_PORT: u16 = 0x64;
+const COMMAND_PORT: u16 = 0x64;
+
+const OUTPUT_BUFFER_FULL: u8 = 1 << 0;
+const INPUT_BUFFER_FULL: u8 = 1 << 1;
+
+const MAX_POLLS: usize = 100_000;
+
+pub const ACK: u8 = 0xFA;
+
+pub mod command {
+    pub const READ_CONFIG: u8 = 0x20;
+    pub const WRITE_CONFIG: u8 = 0x60;
+    pub const ENABLE_AUX: u8 = 0xA8;
+    pub const WRITE_AUX: u8 = 0xD4;
+}
+
+pub mod config {
+    pub const KEYBOARD_INTERRUPT: u8 = 1 << 0;
+    pub const AUX_INTERRUPT: u8 = 1 << 1;
+    pub const AUX_CLOCK_DISABLED: u8 = 1 << 5;
+}
+
+fn status() -> u8 {
+    unsafe { Port::new(STATUS_PORT).read() }
+}
+
+fn wait_for(mask: u8, set: bool) -> bool {
+    (0..MAX_POLLS).any(|_| (status() & mask != 0) == set)
+}
+
+pub fn write_command(command: u8) {
+    if wait_for(INPUT_BUFFER_FULL, false) {
+        unsafe { Port::new(COMMAND_PORT).write(command) }
+    }
+}
+
+pub fn write_data(data: u8) {
+    if wait_for(INPUT_BUFFER_FULL, false) {
+        unsafe { Port::new(DATA_PORT).write(data) }
+    }
+}
+
+pub fn read_data() -> Option<u8> {
+    wait_for(OUTPUT_BUFFER_FULL, true).then(|| unsafe { Port::new(DATA_PORT).read() })
+}
+
+pub fn send_to_mouse(byte: u8) -> Option<u8> {
+    write_command(command::WRITE_AUX);
+    write_data(byte);
+    read_data()
+}
+
+pub fn send_to_keyboard(byte: u8) -> Option<u8> {
+    write_data(byte);
+    read_data()
+}
+
+// The controller still raises IRQ1 for the replies polled by f, which would then be
+// taken for scancodes, so the keyboard interrupt is turned off meanwhile
+pub fn without_keyboard_interrupt<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    write_command(command::READ_CONFIG);
+    let saved_config = read_data();
+    if let Some(controller_config) = saved_config {
+        write_command(command::WRITE_CONFIG);
+        write_data(controller_config & !config::KEYBOARD_INTERRUPT);
+    }
+    let ret = f();
+    if let Some(controller_config) = saved_config {
+        write_command(command::WRITE_CONFIG);
+        write_data(controller_config);
+    }
+    ret
+}
@@ -0,0 +1,82 @@
+use crate::port::Port;
+
+const ADDRESS_PORT: u16 = 0x70;
+const DATA_PORT: u16 = 0x71;
+
+mod register {
+    pub const SECONDS: u8 = 0x00;
+    pub const MINUTES: u8 = 0x02;
+    pub const HOURS: u8 = 0x04;
+    pub const STATUS_A: u8 = 0x0A;
+    pub const STATUS_B: u8 = 0x0B;
+}
+
+const UPDATE_IN_PROGRESS: u8 = 1 << 7; // status A
+const HOURS_24: u8 = 1 << 1; // status B
+const BINARY: u8 = 1 << 2; // status B
+const PM: u8 = 1 << 7; // hours in 12 hour mode
+
+const MAX_ATTEMPTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+fn read(register: u8) -> u8 {
+    unsafe { Port::new(ADDRESS_PORT).write(register) }
+    unsafe { Port::new(DATA_PORT).read() }
+}
+
+fn read_raw() -> Option<[u8; 3]> {
+    if read(register::STATUS_A) & UPDATE_IN_PROGRESS != 0 {
+        return None;
+    }
+    Some([
+        read(register::HOURS),
+        read(register::MINUTES),
+        read(register::SECONDS),
+    ])
+}
+
+const fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+// The same values read twice in a row can't be torn by an update
+pub fn read_time() -> Option<Time> {
+    let mut previous = None;
+    for _ in 0..MAX_ATTEMPTS {
+        let current = read_raw();
+        if current.is_some() && current == previous {
+            return current.map(|raw| decode(raw, read(register::STATUS_B)));
+        }
+        previous = current;
+    }
+    None
+}
+
+fn decode([hours, minutes, seconds]: [u8; 3], status_b: u8) -> Time {
+    let convert = |value| {
+        if status_b & BINARY == 0 {
+            from_bcd(value)
+        } else {
+            value
+        }
+    };
+    let mut hour = convert(hours & !PM);
+    if status_b & HOURS_24 == 0 {
+        // 12 AM is midnight and 12 PM is noon
+        hour = hour.rem_euclid(12);
+        if hours & PM != 0 {
+            hour += 12;
+        }
+    }
+    Time {
+        hours: hour,
+        minutes: convert(minutes),
+        seconds: convert(seconds),
+    }
+}
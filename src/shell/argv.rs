@@ -0,0 +1,197 @@
+use super::{
+    MAX_COMMAND_LEN,
+    variables::{Variables, is_name_byte, is_name_start},
+};
+
+pub const MAX_ARGS: usize = 32;
+// Expansions can make the words longer than the command line
+const MAX_ARGV_LEN: usize = 2 * MAX_COMMAND_LEN;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    UnterminatedQuote,
+    TrailingBackslash,
+    TooManyArguments,
+    TooLong,
+    BadSubstitution,
+}
+
+impl Error {
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::UnterminatedQuote => "unterminated quote",
+            Self::TrailingBackslash => "trailing backslash",
+            Self::TooManyArguments => "too many arguments",
+            Self::TooLong => "command too long",
+            Self::BadSubstitution => "bad substitution",
+        }
+    }
+}
+
+// The words of a command line, unquoted, unescaped and expanded into a buffer of their own.
+// Like sh: single quotes keep everything, double quotes let a backslash escape \, " and $,
+// and $? or the variables in $NAME and ${NAME} are expanded outside single quotes.
+// Unquoted variables are split into words on blanks, unset ones are empty.
+#[derive(Clone, Copy)]
+pub struct Argv {
+    buffer: [u8; MAX_ARGV_LEN],
+    used: usize,
+    ends: [usize; MAX_ARGS],
+    len: usize,
+    // The leading words written NAME=value, quoting or expanding the name makes it a command
+    assignments: usize,
+}
+
+// Checked on the line as typed, before anything is unquoted or expanded
+fn starts_assignment(rest: &[u8]) -> bool {
+    let name_len = rest
+        .iter()
+        .position(|&byte| !is_name_byte(byte))
+        .unwrap_or(rest.len());
+    rest.first().is_some_and(|&byte| is_name_start(byte)) && rest.get(name_len) == Some(&b'=')
+}
+
+impl Argv {
+    pub fn parse(line: &[u8], variables: &Variables, status: u8) -> Result<Self, Error> {
+        let mut argv = Self {
+            buffer: [0; MAX_ARGV_LEN],
+            used: 0,
+            ends: [0; MAX_ARGS],
+            len: 0,
+            assignments: 0,
+        };
+        let mut in_word = false;
+        // Like sh, the value of an assignment isn't split
+        let mut assigning = false;
+        let mut quote = None;
+        let mut bytes = line.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            if !in_word && argv.assignments == argv.len {
+                assigning = starts_assignment(&line[line.len() - bytes.len() - 1..]);
+            }
+            match (quote, byte) {
+                (None, b' ' | b'\t') => {
+                    if in_word {
+                        argv.end_word(assigning)?;
+                        in_word = false;
+                    }
+                    continue;
+                }
+                (None, b'\'' | b'"') => quote = Some(byte),
+                (Some(open), _) if byte == open => quote = None,
+                (None, b'\\') => argv.push(bytes.next().ok_or(Error::TrailingBackslash)?)?,
+                (Some(b'"'), b'\\') => match bytes.next().ok_or(Error::UnterminatedQuote)? {
+                    escaped @ (b'\\' | b'"' | b'$') => argv.push(escaped)?,
+                    other => {
+                        argv.push(b'\\')?;
+                        argv.push(other)?;
+                    }
+                },
+                (None | Some(b'"'), b'$') if bytes.next_if_eq(&b'?').is_some() => {
+                    argv.push_number(status)?;
+                }
+                (None | Some(b'"'), b'$')
+                    if bytes
+                        .peek()
+                        .is_some_and(|&next| next == b'{' || is_name_start(next)) =>
+                {
+                    let braced = bytes.next_if_eq(&b'{').is_some();
+                    let start = line.len() - bytes.len();
+                    while bytes.next_if(|&next| is_name_byte(next)).is_some() {}
+                    let name = &line[start..line.len() - bytes.len()];
+                    if braced && (name.is_empty() || bytes.next_if_eq(&b'}').is_none()) {
+                        return Err(Error::BadSubstitution);
+                    }
+                    let value = variables.get(name).unwrap_or_default();
+                    if quote.is_none() && !assigning {
+                        in_word = argv.push_split(value, in_word)?;
+                        continue;
+                    }
+                    for &value_byte in value {
+                        argv.push(value_byte)?;
+                    }
+                }
+                _ => argv.push(byte)?,
+            }
+            // Quotes start a word even when nothing is between them
+            in_word = true;
+        }
+        if quote.is_some() {
+            return Err(Error::UnterminatedQuote);
+        }
+        if in_word {
+            argv.end_word(assigning)?;
+        }
+        Ok(argv)
+    }
+
+    const fn push(&mut self, byte: u8) -> Result<(), Error> {
+        if self.used == MAX_ARGV_LEN {
+            return Err(Error::TooLong);
+        }
+        self.buffer[self.used] = byte;
+        self.used += 1;
+        Ok(())
+    }
+
+    // Blanks end the words, returns whether the last one is still open
+    fn push_split(&mut self, value: &[u8], in_word: bool) -> Result<bool, Error> {
+        let mut open = in_word;
+        for &byte in value {
+            if byte != b' ' && byte != b'\t' {
+                self.push(byte)?;
+                open = true;
+            } else if open {
+                self.end_word(false)?;
+                open = false;
+            }
+        }
+        Ok(open)
+    }
+
+    fn push_number(&mut self, number: u8) -> Result<(), Error> {
+        let digits = [
+            number.div_euclid(100),
+            number.div_euclid(10).rem_euclid(10),
+            number.rem_euclid(10),
+        ];
+        let first = digits.iter().position(|&digit| digit != 0).unwrap_or(2);
+        for digit in &digits[first..] {
+            self.push(b'0' + digit)?;
+        }
+        Ok(())
+    }
+
+    const fn end_word(&mut self, assignment: bool) -> Result<(), Error> {
+        if self.len == MAX_ARGS {
+            return Err(Error::TooManyArguments);
+        }
+        if assignment {
+            self.assignments += 1;
+        }
+        self.ends[self.len] = self.used;
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&[u8]> {
+        if idx >= self.len {
+            return None;
+        }
+        let start = if idx == 0 { 0 } else { self.ends[idx - 1] };
+        Some(&self.buffer[start..self.ends[idx]])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.len).filter_map(|idx| self.get(idx))
+    }
+
+    pub const fn assignments(&self) -> usize {
+        self.assignments
+    }
+
+    // Without the command name
+    pub fn args(&self) -> impl Iterator<Item = &[u8]> {
+        self.iter().skip(1)
+    }
+}
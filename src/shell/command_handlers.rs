@@ -1,15 +1,50 @@
 use {
-    super::Shell,
+    super::{Shell, argv::Argv, script, variables},
     crate::{
+        dispi, initrd,
+        keyboard::{KEYBOARD, KeyCode, repeat::Typematic},
         port::Port,
         print, println,
-        vga_buffer::{VGA_WIDTH, WRITER},
+        vga_buffer::{
+            CursorShape, LOG_TERMINAL, MAX_TERMINALS, StatusBar, WRITER,
+            canvas::{self, Canvas},
+            font::GLYPH_HEIGHT,
+            mode::TextMode,
+        },
+    },
+    core::{
+        arch::asm,
+        fmt::{self, Write as _},
+        ptr::addr_of,
     },
-    core::{arch::asm, ptr::addr_of},
     lazy_static::lazy_static,
 };
 
 const HEXDUMP_LINE_SIZE: usize = 16;
+const MESSAGE_SIZE: usize = 64;
+pub const NOT_FOUND_STATUS: u8 = 127;
+pub const PARSE_ERROR_STATUS: u8 = 2;
+// 128 + SIGINT, for a line abandoned with Ctrl+C
+pub const INTERRUPTED_STATUS: u8 = 130;
+const BALL_SIZE: usize = 16;
+const MAX_SOURCE_DEPTH: usize = 8;
+// 80 columns and 25 rows of 8x16 glyphs, the shell lays out no smaller screen
+const MIN_VBE_WIDTH: u16 = 640;
+const MIN_VBE_HEIGHT: u16 = 400;
+const TEST_USAGE: &str =
+    "<text> | -z|-n <text> | <text> =|!= <text> | <n> -eq|-ne|-lt|-le|-gt|-ge <n>";
+const TTY_NUMBERS: [&[u8]; MAX_TERMINALS] = [
+    b"1", b"2", b"3", b"4", b"5", b"6", b"7", b"8", b"9", b"10", b"11", b"12",
+];
+const MODE_NAMES: [&[u8]; TextMode::ALL.len()] = {
+    let mut names: [&[u8]; TextMode::ALL.len()] = [&[]; TextMode::ALL.len()];
+    let mut idx = 0;
+    while idx < names.len() {
+        names[idx] = TextMode::ALL[idx].name().as_bytes();
+        idx += 1;
+    }
+    names
+};
 
 unsafe extern "C" {
     static gdt_start: usize;
@@ -42,7 +77,9 @@ fn hexdump(start: usize, end: usize) {
     let mut last_line = [0; HEXDUMP_LINE_SIZE];
     let mut line = [0; HEXDUMP_LINE_SIZE];
     let mut last_was_same = false;
-    for (i, current) in (start..end).step_by(HEXDUMP_LINE_SIZE).enumerate() {
+    // Whole lines are read, so the last one must end before the address space does
+    let clamped_end = end.min(usize::MAX - (HEXDUMP_LINE_SIZE - 1));
+    for (i, current) in (start..clamped_end).step_by(HEXDUMP_LINE_SIZE).enumerate() {
         for (j, cell) in line.iter_mut().enumerate() {
             *cell = unsafe { *((current + j) as *const u8) };
         }
@@ -55,11 +92,7 @@ fn hexdump(start: usize, end: usize) {
                 }
             }
             print!(" |");
-            for byte in line {
-                WRITER
-                    .lock()
-                    .write_byte(if byte == 0x0a { 0x20 } else { byte });
-            }
+            WRITER.lock().write_glyphs(&line);
             println!("|");
             last_line = line;
             last_was_same = false;
@@ -70,38 +103,657 @@ fn hexdump(start: usize, end: usize) {
     }
 }
 
+#[expect(clippy::unnecessary_wraps)] // the signature of every handler
+fn echo(_: &mut Shell, command: &Argv) -> CommandResult {
+    for (idx, arg) in command.args().enumerate() {
+        if idx > 0 {
+            print!(" ");
+        }
+        WRITER.lock().write_bytes(arg);
+    }
+    println!();
+    Ok(())
+}
+
+// Decimal, or hexadecimal after 0x
+fn parse_number(arg: &[u8]) -> Option<u32> {
+    let text = core::str::from_utf8(arg).ok()?;
+    text.strip_prefix("0x").map_or_else(
+        || text.parse().ok(),
+        |hex| u32::from_str_radix(hex, 16).ok(),
+    )
+}
+
+fn hexdump_command(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let range = match (args.next(), args.next(), args.next()) {
+        (Some(start), length, None) => {
+            parse_number(start).zip(length.map_or(Some(256), parse_number))
+        }
+        _ => None,
+    };
+    let (start, length) = range.ok_or(Error::Usage("<address> [length]"))?;
+    let address = start as usize;
+    hexdump(address, address.saturating_add(length as usize));
+    Ok(())
+}
+
+fn parse_timing(rate: &[u8], delay: &[u8]) -> Result<(u32, u32), Error> {
+    parse_number(rate)
+        .filter(|&rate_hz| rate_hz > 0)
+        .zip(parse_number(delay))
+        .ok_or(Error::Failed("invalid rate or delay"))
+}
+
+fn set_software_repeat_for_arrows(enabled: bool) {
+    let mut keyboard = KEYBOARD.lock();
+    for code in [
+        KeyCode::ArrowUp,
+        KeyCode::ArrowRight,
+        KeyCode::ArrowDown,
+        KeyCode::ArrowLeft,
+    ] {
+        keyboard.software_repeat_mut().set_enabled(code, enabled);
+    }
+}
+
+fn kbdrate(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    match (args.next(), args.next(), args.next(), args.next()) {
+        (None, ..) => {}
+        (Some(b"hard"), None, ..) => set_software_repeat_for_arrows(false),
+        (Some(b"soft"), Some(rate), Some(delay), None) => {
+            let (rate_hz, delay_ms) = parse_timing(rate, delay)?;
+            KEYBOARD
+                .lock()
+                .software_repeat_mut()
+                .set_timing(rate_hz, delay_ms);
+            set_software_repeat_for_arrows(true);
+        }
+        (Some(rate), Some(delay), None, _) => {
+            let (rate_hz, delay_ms) = parse_timing(rate, delay)?;
+            if KEYBOARD
+                .lock()
+                .set_typematic(Typematic::closest(rate_hz, delay_ms))
+                .is_err()
+            {
+                return Err(Error::Failed("the keyboard did not acknowledge"));
+            }
+        }
+        _ => return Err(Error::Usage("[[soft] <rate> <delay> | hard]")),
+    }
+    let keyboard = KEYBOARD.lock();
+    let typematic = keyboard.typematic();
+    println!(
+        "hardware: {}.{} cps after {} ms",
+        typematic.rate_decihertz().div_euclid(10),
+        typematic.rate_decihertz().rem_euclid(10),
+        typematic.delay_ms(),
+    );
+    let software_repeat = keyboard.software_repeat();
+    if software_repeat.is_enabled(KeyCode::ArrowUp) {
+        println!(
+            "software (arrows): {} cps after {} ms",
+            software_repeat.rate_hz(),
+            software_repeat.delay_ms(),
+        );
+    } else {
+        println!("software: off");
+    }
+    Ok(())
+}
+
+// Terminals are numbered from 1 like the Alt+F keys
+fn parse_tty(arg: &[u8]) -> Option<usize> {
+    let idx = (parse_number(arg)? as usize).checked_sub(1)?;
+    (idx < MAX_TERMINALS).then_some(idx)
+}
+
+fn openvt(shell: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let requested = match (args.next(), args.next()) {
+        (None, _) => None,
+        (Some(arg), None) => Some(parse_tty(arg)),
+        _ => return Err(Error::Usage("[tty]")),
+    };
+    let slot = requested.unwrap_or_else(|| {
+        let writer = WRITER.lock();
+        (0..LOG_TERMINAL).find(|&free| !writer.is_open(free))
+    });
+    match slot {
+        Some(idx) if shell.open_terminal(idx) => {
+            println!("opened tty{}", idx + 1);
+            Ok(())
+        }
+        Some(idx) => Err(Error::Formatted(Message::new(format_args!(
+            "tty{} is already open",
+            idx + 1
+        )))),
+        None => Err(Error::Failed("no such terminal")),
+    }
+}
+
+fn closevt(shell: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let (Some(arg), None) = (args.next(), args.next()) else {
+        return Err(Error::Usage("<tty>"));
+    };
+    match parse_tty(arg) {
+        Some(idx) if shell.close_terminal(idx) => {
+            println!("closed tty{}", idx + 1);
+            Ok(())
+        }
+        Some(idx) => Err(Error::Formatted(Message::new(format_args!(
+            "tty{} is displayed, closed or the kernel log",
+            idx + 1
+        )))),
+        None => Err(Error::Failed("no such terminal")),
+    }
+}
+
+fn history(shell: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let history = &mut shell.command_mut().history;
+    match (args.next(), args.next()) {
+        (None, _) => {
+            for (number, entry) in (history.first_number()..).zip(history.iter()) {
+                print!("{number:5}  ");
+                WRITER.lock().write_bytes(entry);
+                println!();
+            }
+        }
+        (Some(b"-c"), None) => history.clear(),
+        _ => return Err(Error::Usage("[-c]")),
+    }
+    Ok(())
+}
+
+// The shape of the insert mode, overwrite mode shows another one
+fn cursor(shell: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let shape = match (args.next(), args.next()) {
+        (Some(b"underline"), None) => CursorShape::Underline,
+        (Some(b"half"), None) => CursorShape::Half,
+        (Some(b"block"), None) => CursorShape::Block,
+        _ => return Err(Error::Usage("<underline | half | block>")),
+    };
+    let buffer = shell.command_mut();
+    buffer.cursor_shape = shape;
+    WRITER.lock().set_cursor_shape(buffer.shown_cursor_shape());
+    Ok(())
+}
+
+fn font(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    match (args.next(), args.next()) {
+        (Some(b"bios"), None) if !WRITER.lock().restore_bios_font() => {
+            Err(Error::Failed("no BIOS font on the framebuffer"))
+        }
+        (Some(b"bios"), None) => Ok(()),
+        (Some(b"kfs"), None) => {
+            WRITER.lock().load_default_font();
+            Ok(())
+        }
+        _ => Err(Error::Usage("<bios | kfs>")),
+    }
+}
+
+// A single character stands for itself, anything longer is its code
+fn parse_glyph_code(arg: &[u8]) -> Option<u8> {
+    match *arg {
+        [byte] => Some(byte),
+        _ => u8::try_from(parse_number(arg)?).ok(),
+    }
+}
+
+fn parse_hex_byte(arg: &[u8]) -> Option<u8> {
+    u8::from_str_radix(core::str::from_utf8(arg).ok()?, 16).ok()
+}
+
+fn glyph(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let code = args.next().and_then(parse_glyph_code);
+    let mut rows = [0; GLYPH_HEIGHT];
+    let mut height = 0;
+    let mut valid = true;
+    for arg in args {
+        let (Some(row), Some(value)) = (rows.get_mut(height), parse_hex_byte(arg)) else {
+            valid = false;
+            break;
+        };
+        *row = value;
+        height += 1;
+    }
+    match code {
+        Some(glyph_code) if valid && WRITER.lock().set_glyph(glyph_code, &rows[..height]) => Ok(()),
+        _ => Err(Error::Usage("<char | code> <8 or 16 hex rows>")),
+    }
+}
+
+fn statusbar(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let status_bar = match (args.next(), args.next()) {
+        (None, _) => {
+            println!("{:?}", WRITER.lock().status_bar());
+            return Ok(());
+        }
+        (Some(b"top"), None) => StatusBar::Top,
+        (Some(b"bottom"), None) => StatusBar::Bottom,
+        (Some(b"off"), None) => StatusBar::Hidden,
+        _ => return Err(Error::Usage("[top | bottom | off]")),
+    };
+    WRITER.lock().set_status_bar(status_bar);
+    Ok(())
+}
+
+fn mode(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let requested = match (args.next(), args.next()) {
+        (None, _) => {
+            let writer = WRITER.lock();
+            match (writer.mode(), writer.framebuffer()) {
+                (Some(current), _) => println!("{}", current.name()),
+                (None, Some(framebuffer)) => println!(
+                    "framebuffer {}x{}x{} ({}x{})",
+                    framebuffer.width(),
+                    framebuffer.height(),
+                    framebuffer.bits_per_pixel(),
+                    writer.width(),
+                    writer.height()
+                ),
+                (None, None) => {}
+            }
+            return Ok(());
+        }
+        (Some(name), None) => TextMode::from_name(name),
+        _ => None,
+    };
+    let mode = requested.ok_or(Error::Usage("[80x25 | 80x50 | 90x30 | 90x60]"))?;
+    if !WRITER.lock().set_mode(mode) {
+        return Err(Error::Failed("only in VGA text mode"));
+    }
+    Ok(())
+}
+
+// Lit from the top left, each channel of the mask is 0 or 1 and the corners are transparent
+fn ball_sprite(mask: [u8; 3]) -> [u8; BALL_SIZE * BALL_SIZE] {
+    let mut sprite = [0; BALL_SIZE * BALL_SIZE];
+    // Doubled coordinates from the center, so that it falls between pixels
+    let coords = (-15..16).step_by(2);
+    let cells = coords
+        .clone()
+        .flat_map(|y| coords.clone().map(move |x| (x, y)));
+    for (pixel, (x, y)) in sprite.iter_mut().zip(cells) {
+        if x * x + y * y >= 256 {
+            continue;
+        }
+        let (light_x, light_y) = (x + 6, y + 6);
+        let darkness = u8::try_from((light_x * light_x + light_y * light_y) >> 7).unwrap_or(5);
+        let level = 5 - darkness.min(5);
+        *pixel = canvas::cube(level * mask[0], level * mask[1], level * mask[2]);
+    }
+    sprite
+}
+
+fn draw_demo(canvas: &mut Canvas<'_>) {
+    // The whole palette
+    for index in 0..=u8::MAX {
+        let (x, y) = (i32::from(index & 0x0F), i32::from(index >> 4));
+        canvas.fill_rect(8 + 11 * x, 12 + 11 * y, 10, 10, index);
+    }
+    canvas.rect(5, 9, 182, 182, 15);
+    for step in 0..16 {
+        let end = (196 + 8 * i32::from(step), 8);
+        canvas.line((256, 100), end, canvas::cube(5, step >> 2, 5 - (step >> 2)));
+    }
+    for (x, mask) in [(200, [1, 0, 0]), (228, [0, 1, 0]), (256, [0, 0, 1])] {
+        canvas.blit(x, 140, BALL_SIZE, &ball_sprite(mask), Some(0));
+    }
+}
+
+fn graphics(_: &mut Shell, _: &Argv) -> CommandResult {
+    let mut writer = WRITER.lock();
+    let mut canvas = writer
+        .enter_graphics()
+        .ok_or(Error::Failed("only in VGA text mode"))?;
+    draw_demo(&mut canvas);
+    Ok(())
+}
+
+// <width>x<height>[x<bits per pixel>], 32 bits per pixel by default
+fn parse_resolution(arg: &[u8]) -> Option<(u16, u16, u8)> {
+    let mut parts = arg.split(|&byte| byte == b'x');
+    let width = u16::try_from(parse_number(parts.next()?)?)
+        .ok()
+        .filter(|&width| width >= MIN_VBE_WIDTH)?;
+    let height = u16::try_from(parse_number(parts.next()?)?)
+        .ok()
+        .filter(|&height| height >= MIN_VBE_HEIGHT)?;
+    let bits_per_pixel = match parts.next() {
+        Some(part) => u8::try_from(parse_number(part)?).ok()?,
+        None => 32,
+    };
+    parts
+        .next()
+        .is_none()
+        .then_some((width, height, bits_per_pixel))
+}
+
+fn vbe(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let requested = match (args.next(), args.next()) {
+        (Some(b"off"), None) => {
+            if !WRITER.lock().switch_to_text(dispi::disable) {
+                return Err(Error::Failed("the text mode was not left with vbe"));
+            }
+            return Ok(());
+        }
+        (Some(resolution), None) => parse_resolution(resolution),
+        _ => None,
+    };
+    let (width, height, bits_per_pixel) =
+        requested.ok_or(Error::Usage("<width>x<height>[x<15 | 16 | 24 | 32>] | off"))?;
+    if !dispi::is_present() {
+        return Err(Error::Failed("no Bochs VBE display"));
+    }
+    if !WRITER
+        .lock()
+        .switch_to_framebuffer(|| dispi::set_mode(width, height, bits_per_pixel))
+    {
+        return Err(Error::Formatted(Message::new(format_args!(
+            "{width}x{height}x{bits_per_pixel} unavailable"
+        ))));
+    }
+    Ok(())
+}
+
+#[expect(clippy::unnecessary_wraps)] // the signature of every handler
+fn set(shell: &mut Shell, _: &Argv) -> CommandResult {
+    let mut writer = WRITER.lock();
+    for (name, value) in shell.command().variables.iter() {
+        writer.write_bytes(name);
+        writer.write_byte(b'=');
+        writer.write_bytes(value);
+        writer.write_byte(b'\n');
+    }
+    Ok(())
+}
+
+fn unset(shell: &mut Shell, command: &Argv) -> CommandResult {
+    if command.args().next().is_none() {
+        return Err(Error::Usage("<name>..."));
+    }
+    for name in command.args() {
+        shell.command_mut().variables.unset(name);
+    }
+    Ok(())
+}
+
+fn compare_numbers(left: &[u8], operator: &[u8], right: &[u8]) -> Option<bool> {
+    let (left_number, right_number) = (parse_number(left)?, parse_number(right)?);
+    Some(match operator {
+        b"-eq" => left_number == right_number,
+        b"-ne" => left_number != right_number,
+        b"-lt" => left_number < right_number,
+        b"-le" => left_number <= right_number,
+        b"-gt" => left_number > right_number,
+        b"-ge" => left_number >= right_number,
+        _ => return None,
+    })
+}
+
+// The condition of if and while
+fn test(_: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let truth = match (args.next(), args.next(), args.next(), args.next()) {
+        (None, ..) => false,
+        (Some(text), None, ..) | (Some(b"-n"), Some(text), None, _) => !text.is_empty(),
+        (Some(b"-z"), Some(text), None, _) => text.is_empty(),
+        (Some(left), Some(b"="), Some(right), None) => left == right,
+        (Some(left), Some(b"!="), Some(right), None) => left != right,
+        (Some(left), Some(operator), Some(right), None) => {
+            compare_numbers(left, operator, right).ok_or(Error::Usage(TEST_USAGE))?
+        }
+        _ => return Err(Error::Usage(TEST_USAGE)),
+    };
+    if truth { Ok(()) } else { Err(Error::Status(1)) }
+}
+
+// Quoted so that it can be typed back
+fn print_alias(name: &[u8], value: &[u8]) {
+    let mut writer = WRITER.lock();
+    writer.write_bytes(b"alias ");
+    writer.write_bytes(name);
+    writer.write_bytes(b"='");
+    for &byte in value {
+        if byte == b'\'' {
+            writer.write_bytes(b"'\\''");
+        } else {
+            writer.write_byte(byte);
+        }
+    }
+    writer.write_bytes(b"'\n");
+}
+
+// alias name=value sets one, alias name shows it and alias alone shows them all
+fn alias(shell: &mut Shell, command: &Argv) -> CommandResult {
+    if command.args().next().is_none() {
+        for (name, value) in shell.aliases.iter() {
+            print_alias(name, value);
+        }
+        return Ok(());
+    }
+    let mut result = Ok(());
+    for word in command.args() {
+        if let Some((name, value)) = variables::split_assignment(word) {
+            shell
+                .aliases
+                .set(name, value)
+                .map_err(|error| Error::Failed(error.message()))?;
+        } else if let Some(value) = shell.aliases.get(word) {
+            print_alias(word, value);
+        } else {
+            result = Err(Error::Failed("no such alias"));
+        }
+    }
+    result
+}
+
+fn unalias(shell: &mut Shell, command: &Argv) -> CommandResult {
+    if command.args().next().is_none() {
+        return Err(Error::Usage("<name>..."));
+    }
+    let mut result = Ok(());
+    for name in command.args() {
+        if !shell.aliases.unset(name) {
+            result = Err(Error::Failed("no such alias"));
+        }
+    }
+    result
+}
+
+// Runs a boot module in this shell, its status is the one of its last command
+fn source(shell: &mut Shell, command: &Argv) -> CommandResult {
+    let mut args = command.args();
+    let (Some(name), None) = (args.next(), args.next()) else {
+        return Err(Error::Usage("<file>"));
+    };
+    let script = initrd::find(name).ok_or(Error::Failed("no such file"))?;
+    if shell.sourcing == MAX_SOURCE_DEPTH {
+        return Err(Error::Failed("too many nested scripts"));
+    }
+    shell.sourcing += 1;
+    let result = script::run(shell, script);
+    shell.sourcing -= 1;
+    result.map_err(|error| Error::Failed(error.message()))?;
+    match shell.command().status {
+        0 => Ok(()),
+        status => Err(Error::Status(status)),
+    }
+}
+
+// A failure message with its arguments, what doesn't fit is dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Message {
+    bytes: [u8; MESSAGE_SIZE],
+    len: usize,
+}
+
+impl Message {
+    pub fn new(args: fmt::Arguments<'_>) -> Self {
+        let mut message = Self {
+            bytes: [0; MESSAGE_SIZE],
+            len: 0,
+        };
+        message.write_fmt(args).unwrap();
+        message
+    }
+}
+
+impl fmt::Write for Message {
+    // Whole characters only, so that the message stays valid UTF-8
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let end = self.len + c.len_utf8();
+            if end > MESSAGE_SIZE {
+                break;
+            }
+            c.encode_utf8(&mut self.bytes[self.len..end]);
+            self.len = end;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default())
+    }
+}
+
+// Printed by the shell after the name of the command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    Usage(&'static str),
+    Failed(&'static str),
+    Formatted(Message),
+    // Nothing to print, like a false test
+    Status(u8),
+}
+
+impl Error {
+    // For $?, 0 is success
+    pub const fn status(self) -> u8 {
+        match self {
+            Self::Failed(_) | Self::Formatted(_) => 1,
+            Self::Usage(_) => 2,
+            Self::Status(status) => status,
+        }
+    }
+}
+
+pub type CommandResult = Result<(), Error>;
+// The words that can be typed as the argument at an index, 0 being the first after the name
+pub type Completer = fn(usize) -> &'static [&'static [u8]];
+
+// For the commands that only take one argument
+const fn first_argument(idx: usize, words: &'static [&'static [u8]]) -> &'static [&'static [u8]] {
+    if idx == 0 { words } else { &[] }
+}
+
 #[derive(Clone, Copy)]
 pub struct CommandHandler {
     pub name: &'static [u8],
     pub description: &'static [u8],
-    pub handler: fn(&Shell), // Does it really make sense to take a shell as argument?
+    pub handler: fn(&mut Shell, &Argv) -> CommandResult,
+    pub completer: Option<Completer>,
 }
 
 pub const COMMAND_HANDLERS: &[CommandHandler] = &[
+    CommandHandler {
+        name: b"alias",
+        description: b"Set or show the aliases of commands.",
+        handler: alias,
+        completer: None,
+    },
     CommandHandler {
         name: b"clear",
         description: b"Clear the screen.",
-        handler: |_: &Shell| WRITER.lock().clear_screen(),
+        handler: |_: &mut Shell, _: &Argv| {
+            WRITER.lock().clear_screen();
+            Ok(())
+        },
+        completer: None,
+    },
+    CommandHandler {
+        name: b"closevt",
+        description: b"Close a virtual terminal.",
+        handler: closevt,
+        completer: Some(|idx: usize| first_argument(idx, &TTY_NUMBERS)),
+    },
+    CommandHandler {
+        name: b"cursor",
+        description: b"Set the cursor shape, Insert toggles overwrite.",
+        handler: cursor,
+        completer: Some(|idx: usize| first_argument(idx, &[b"underline", b"half", b"block"])),
+    },
+    CommandHandler {
+        name: b"echo",
+        description: b"Print the arguments, $? is the status of the last command.",
+        handler: echo,
+        completer: None,
     },
     CommandHandler {
         name: b"exit",
         description: b"Exit the system.",
-        handler: |_: &Shell| exit_qemu(QemuExitCode::Success),
+        handler: |_: &mut Shell, _: &Argv| {
+            exit_qemu(QemuExitCode::Success);
+            Ok(())
+        },
+        completer: None,
+    },
+    CommandHandler {
+        name: b"false",
+        description: b"Fail, with status 1.",
+        handler: |_: &mut Shell, _: &Argv| Err(Error::Status(1)),
+        completer: None,
+    },
+    CommandHandler {
+        name: b"font",
+        description: b"Switch between the BIOS font and the kfs one.",
+        handler: font,
+        completer: Some(|idx: usize| first_argument(idx, &[b"bios", b"kfs"])),
+    },
+    CommandHandler {
+        name: b"glyph",
+        description: b"Redefine a character of the current font.",
+        handler: glyph,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"graphics",
+        description: b"Draw in VGA mode 13h until a key is pressed.",
+        handler: graphics,
+        completer: None,
     },
     CommandHandler {
         name: b"halt",
         description: b"Halt the system.",
-        handler: |_: &Shell| {
+        handler: |_: &mut Shell, _: &Argv| {
             unsafe { asm!("cli") }
             print!("System halted.");
-            WRITER.lock().set_cursor(VGA_WIDTH);
+            let mut writer = WRITER.lock();
+            let width = writer.width();
+            writer.set_cursor(width);
             unsafe { asm!("hlt") }
+            Ok(())
         },
+        completer: None,
     },
     CommandHandler {
         name: b"help",
         description: b"Show this help message.",
-        handler: |_: &Shell| {
+        handler: |_: &mut Shell, _: &Argv| {
             println!("Available commands:");
             let max_length = COMMAND_HANDLERS
                 .iter()
@@ -115,12 +767,44 @@ pub const COMMAND_HANDLERS: &[CommandHandler] = &[
                     core::str::from_utf8(handler.description).unwrap_or("invalid utf-8"),
                 );
             }
+            Ok(())
         },
+        completer: None,
+    },
+    CommandHandler {
+        name: b"hexdump",
+        description: b"Dump memory, 256 bytes by default.",
+        handler: hexdump_command,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"history",
+        description: b"Show or clear (-c) the commands of this terminal.",
+        handler: history,
+        completer: Some(|idx: usize| first_argument(idx, &[b"-c"])),
+    },
+    CommandHandler {
+        name: b"kbdrate",
+        description: b"Show or set the key repeat rate and delay.",
+        handler: kbdrate,
+        completer: Some(|idx: usize| first_argument(idx, &[b"soft", b"hard"])),
+    },
+    CommandHandler {
+        name: b"mode",
+        description: b"Show or change the text mode.",
+        handler: mode,
+        completer: Some(|idx: usize| first_argument(idx, &MODE_NAMES)),
+    },
+    CommandHandler {
+        name: b"openvt",
+        description: b"Open a virtual terminal, Alt+F<n> to show it.",
+        handler: openvt,
+        completer: Some(|idx: usize| first_argument(idx, &TTY_NUMBERS)),
     },
     CommandHandler {
         name: b"pgdt",
         description: b"Print the GDT.",
-        handler: |_: &Shell| {
+        handler: |_: &mut Shell, _: &Argv| {
             for address in (*GDT_START..*GDT_POINTER).step_by(8) {
                 print!("{:#07x}:", address);
                 for i in 0..8 {
@@ -129,21 +813,85 @@ pub const COMMAND_HANDLERS: &[CommandHandler] = &[
                 }
                 println!();
             }
+            Ok(())
         },
+        completer: None,
     },
     CommandHandler {
         name: b"pks",
         description: b"Print the kernel stack.",
-        handler: |_: &Shell| hexdump(*STACK_BOTTOM, *STACK_TOP),
+        handler: |_: &mut Shell, _: &Argv| {
+            hexdump(*STACK_BOTTOM, *STACK_TOP);
+            Ok(())
+        },
+        completer: None,
     },
     CommandHandler {
         name: b"reboot",
         description: b"Reboot the system.",
-        handler: |_: &Shell| unsafe { Port::new(0x64).write(0xfe_u8) },
+        handler: |_: &mut Shell, _: &Argv| {
+            unsafe { Port::new(0x64).write(0xfe_u8) }
+            Ok(())
+        },
+        completer: None,
+    },
+    CommandHandler {
+        name: b"set",
+        description: b"Show the variables, set them with NAME=value.",
+        handler: set,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"source",
+        description: b"Run a script given to the bootloader as a module.",
+        handler: source,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"statusbar",
+        description: b"Show or move the status bar.",
+        handler: statusbar,
+        completer: Some(|idx: usize| first_argument(idx, &[b"top", b"bottom", b"off"])),
+    },
+    CommandHandler {
+        name: b"test",
+        description: b"Compare texts or numbers, for if and while.",
+        handler: test,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"true",
+        description: b"Succeed, with status 0.",
+        handler: |_: &mut Shell, _: &Argv| Ok(()),
+        completer: None,
     },
     CommandHandler {
         name: b"tty",
-        description: b"Show the current screen number.",
-        handler: |shell: &Shell| println!("F{}", shell.screen_idx + 1),
+        description: b"Show the current terminal number.",
+        handler: |shell: &mut Shell, _: &Argv| {
+            println!("tty{}", shell.screen_idx + 1);
+            Ok(())
+        },
+        completer: None,
+    },
+    CommandHandler {
+        name: b"unalias",
+        description: b"Remove aliases.",
+        handler: unalias,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"unset",
+        description: b"Remove variables.",
+        handler: unset,
+        completer: None,
+    },
+    CommandHandler {
+        name: b"vbe",
+        description: b"Switch to a Bochs VBE framebuffer or back to text.",
+        handler: vbe,
+        completer: Some(|idx: usize| {
+            first_argument(idx, &[b"640x480", b"800x600", b"1024x768", b"off"])
+        }),
     },
 ];
@@ -0,0 +1,83 @@
+use super::MAX_COMMAND_LEN;
+
+const HISTORY_LEN: usize = 32;
+
+// The last commands of a screen in a ring, the oldest is forgotten when it's full.
+// Indices go from 0 for the oldest kept entry, numbers count every command ever added.
+pub struct History {
+    entries: [[u8; MAX_COMMAND_LEN]; HISTORY_LEN],
+    lens: [usize; HISTORY_LEN],
+    head: usize,
+    len: usize,
+    added: usize,
+}
+
+impl History {
+    pub const fn new() -> Self {
+        Self {
+            entries: [[0; MAX_COMMAND_LEN]; HISTORY_LEN],
+            lens: [0; HISTORY_LEN],
+            head: 0,
+            len: 0,
+            added: 0,
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    // Of the oldest entry, starting at 1 like sh
+    pub const fn first_number(&self) -> usize {
+        self.added - self.len + 1
+    }
+
+    const fn physical(&self, idx: usize) -> usize {
+        (self.head + idx).rem_euclid(HISTORY_LEN)
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&[u8]> {
+        if idx >= self.len {
+            return None;
+        }
+        let physical = self.physical(idx);
+        Some(&self.entries[physical][..self.lens[physical]])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.len).filter_map(|idx| self.get(idx))
+    }
+
+    // Empty lines and repeats of the last entry aren't kept
+    pub fn push(&mut self, line: &[u8]) {
+        if line.is_empty() || self.len.checked_sub(1).and_then(|last| self.get(last)) == Some(line)
+        {
+            return;
+        }
+        let physical = if self.len == HISTORY_LEN {
+            let oldest = self.head;
+            self.head = self.physical(1);
+            oldest
+        } else {
+            self.len += 1;
+            self.physical(self.len - 1)
+        };
+        self.entries[physical][..line.len()].copy_from_slice(line);
+        self.lens[physical] = line.len();
+        self.added += 1;
+    }
+
+    pub const fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    // The newest entry before idx containing needle
+    pub fn search(&self, needle: &[u8], before: usize) -> Option<usize> {
+        (0..before.min(self.len)).rev().find(|&idx| {
+            self.get(idx).is_some_and(|entry| {
+                needle.is_empty() || entry.windows(needle.len()).any(|window| window == needle)
+            })
+        })
+    }
+}
@@ -1,14 +1,32 @@
+mod argv;
 mod command_handlers;
+mod history;
+mod script;
+mod variables;
 
 use {
     crate::{
-        keyboard::{DecodedKey, KeyCode},
+        clipboard::CLIPBOARD,
+        initrd,
+        keyboard::{DecodedKey, KeyCode, KeyState, Modifiers, events},
+        log,
+        mouse::{MouseButton, MouseEvent},
         println,
-        vga_buffer::{Color, VGA_SCREENS, VGA_WIDTH, WRITER},
+        vga_buffer::{
+            Color, CursorShape, DEBUG_TERMINAL, TERMINAL_SLOTS, VGA_MAX_WIDTH, WARNING_TERMINAL,
+            WRITER, terminal::VirtualTerminal,
+        },
     },
-    command_handlers::{COMMAND_HANDLERS, QemuExitCode, exit_qemu},
+    argv::Argv,
+    command_handlers::{
+        COMMAND_HANDLERS, Error, INTERRUPTED_STATUS, NOT_FOUND_STATUS, PARSE_ERROR_STATUS,
+        QemuExitCode, exit_qemu,
+    },
+    core::ops::Range,
+    history::History,
     lazy_static::lazy_static,
     spin::Mutex,
+    variables::{Aliases, Variables},
 };
 
 // TODO: test profusely, especially special characters
@@ -16,22 +34,86 @@ use {
 
 // Maybe an enum or a transparent struct would be better?
 mod special_char {
+    pub const LINE_START: char = '\x01'; // Ctrl+A
+    pub const BACKWARD: char = '\x02'; // Ctrl+B
+    pub const INTERRUPT: char = '\x03'; // Ctrl+C
+    pub const LINE_END: char = '\x05'; // Ctrl+E
+    pub const FORWARD: char = '\x06'; // Ctrl+F
+    pub const CANCEL: char = '\x07'; // Ctrl+G
     pub const BACKSPACE: char = '\x08';
+    pub const TAB: char = '\x09';
     pub const NEWLINE: char = '\x0a';
+    pub const KILL_TO_END: char = '\x0b'; // Ctrl+K
+    pub const CLEAR_SCREEN: char = '\x0c'; // Ctrl+L
+    pub const REVERSE_SEARCH: char = '\x12'; // Ctrl+R
+    pub const KILL_TO_START: char = '\x15'; // Ctrl+U
+    pub const KILL_WORD: char = '\x17'; // Ctrl+W
+    pub const YANK: char = '\x19'; // Ctrl+Y
     pub const ESCAPE: char = '\x1b';
     pub const DELETE: char = '\x7f';
 }
 
+// Unless PS1 is set, which is cut to leave room for the command
 const PROMPT: &[u8] = b"> ";
-const MAX_COMMAND_LEN: usize = VGA_WIDTH - PROMPT.len() - 1;
+const MAX_PROMPT_LEN: usize = 32;
+const SEARCH_PROMPT: &[u8] = b"(reverse-i-search)`";
+const FAILED_SEARCH_PROMPT: &[u8] = b"(failed reverse-i-search)`";
+const SEARCH_SEPARATOR: &[u8] = b"': ";
+// Longer than the screen is wide, the line after the prompt scrolls horizontally
+const MAX_COMMAND_LEN: usize = 256;
+// An alias and the rest of the command
+const MAX_EXPANDED_LEN: usize = 2 * MAX_COMMAND_LEN;
+const SPACES: [u8; VGA_MAX_WIDTH] = [b' '; VGA_MAX_WIDTH];
 const WELCOME_MARGIN: usize = 2;
 const CORNER_REPEAT: usize = 3; // 1 for something not too weird
+const INITIAL_TERMINALS: usize = 4;
+const PROMPT_COLORS: [Color; 6] = [
+    Color::Pink,
+    Color::LightCyan,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::Yellow,
+    Color::LightBlue,
+];
+const FAILURE_COLOR: Color = Color::Red;
+// Run by every screen before its first prompt, a kfsrc boot module replaces it
+const RC_NAME: &[u8] = b"kfsrc";
+const DEFAULT_RC: &[u8] = include_bytes!("../../initrd/kfsrc");
+
+// Ctrl+R, the line shows the newest entry containing the query
+struct Search {
+    query: [u8; MAX_COMMAND_LEN],
+    len: usize,
+    found: Option<usize>,
+}
 
 struct CommandBuffer {
     buffer: [u8; MAX_COMMAND_LEN],
     len: usize,
     pos: usize,
+    // First byte shown after the prompt
+    scroll: usize,
     color: Color,
+    // Toggled with Insert, shown with another cursor shape
+    overwrite: bool,
+    // Chosen with the cursor command, for the insert mode
+    cursor_shape: CursorShape,
+    // A second Tab in a row lists the candidates
+    tabbed: bool,
+    // The rc script ran, once the screen was first shown
+    started: bool,
+    // Of the last command, for $? and the color of the prompt
+    status: u8,
+    history: History,
+    // The entry shown by the arrows, None for the line being typed, kept in draft meanwhile
+    recalled: Option<usize>,
+    draft: [u8; MAX_COMMAND_LEN],
+    draft_len: usize,
+    search: Option<Search>,
+    // Of the last Ctrl+K, Ctrl+U, Ctrl+W or Alt+Backspace, for Ctrl+Y
+    killed: [u8; MAX_COMMAND_LEN],
+    killed_len: usize,
+    variables: Variables,
 }
 
 impl CommandBuffer {
@@ -40,14 +122,66 @@ impl CommandBuffer {
             buffer: [0; MAX_COMMAND_LEN],
             len: 0,
             pos: 0,
+            scroll: 0,
             color,
+            overwrite: false,
+            cursor_shape: CursorShape::Underline,
+            tabbed: false,
+            started: false,
+            status: 0,
+            history: History::new(),
+            recalled: None,
+            draft: [0; MAX_COMMAND_LEN],
+            draft_len: 0,
+            search: None,
+            killed: [0; MAX_COMMAND_LEN],
+            killed_len: 0,
+            variables: Variables::new(),
+        }
+    }
+
+    fn prompt(&self) -> &[u8] {
+        let prompt = self.variables.get(b"PS1").unwrap_or(PROMPT);
+        &prompt[..prompt.len().min(MAX_PROMPT_LEN)]
+    }
+
+    // A block in overwrite mode, or half of one when the chosen shape already is a block
+    const fn shown_cursor_shape(&self) -> CursorShape {
+        match (self.overwrite, self.cursor_shape) {
+            (false, shape) => shape,
+            (true, CursorShape::Block) => CursorShape::Half,
+            (true, _) => CursorShape::Block,
+        }
+    }
+
+    const fn prompt_color(&self) -> Color {
+        if self.status == 0 {
+            self.color
+        } else {
+            FAILURE_COLOR
         }
     }
 
     fn set_pos(&mut self, pos: usize) {
-        // Breaks if pos > MAX_COMMAND_LEN. Use assert!() ?
         self.pos = pos;
-        WRITER.lock().set_cursor(PROMPT.len() + pos);
+        self.show();
+    }
+
+    // Redraws the line after the prompt, scrolled so that the cursor stays in view.
+    // The last column is left for the cursor at the end of the line.
+    fn show(&mut self) {
+        let mut writer = WRITER.lock();
+        let prompt_len = self.prompt().len();
+        let columns = writer.width().saturating_sub(prompt_len + 1);
+        self.scroll = self
+            .scroll
+            .clamp(self.pos.saturating_sub(columns), self.pos)
+            .min(self.len.saturating_sub(columns));
+        let shown = &self.buffer[self.scroll..self.len.min(self.scroll + columns)];
+        writer.set_cursor(prompt_len);
+        writer.write_bytes(shown);
+        writer.write_bytes(&SPACES[shown.len()..columns]);
+        writer.set_cursor(prompt_len + self.pos - self.scroll);
     }
 
     fn move_left(&mut self) {
@@ -62,7 +196,7 @@ impl CommandBuffer {
         }
     }
 
-    fn trimmed(&self) -> &[u8] {
+    const fn trimmed_range(&self) -> Range<usize> {
         // b'\0' and b'\xff' are also whitespace, but they can't be typed for now
         let mut start = 0;
         while start < self.len && self.buffer[start] == b' ' {
@@ -72,194 +206,689 @@ impl CommandBuffer {
         while end > start && self.buffer[end - 1] == b' ' {
             end -= 1;
         }
-        &self.buffer[start..end]
+        start..end
+    }
+
+    // Like readline, Alt+B and Alt+F stop at letters and digits while Ctrl+W stops at spaces
+    fn word_start(&self, is_word: fn(&u8) -> bool) -> usize {
+        let before = &self.buffer[..self.pos];
+        let end = before.iter().rposition(is_word).map_or(0, |last| last + 1);
+        before[..end]
+            .iter()
+            .rposition(|byte| !is_word(byte))
+            .map_or(0, |space| space + 1)
+    }
+
+    fn word_end(&self) -> usize {
+        let after = &self.buffer[self.pos..self.len];
+        let start = after
+            .iter()
+            .position(u8::is_ascii_alphanumeric)
+            .unwrap_or(after.len());
+        let len = after[start..]
+            .iter()
+            .position(|byte| !byte.is_ascii_alphanumeric())
+            .unwrap_or(after.len() - start);
+        self.pos + start + len
+    }
+
+    // Arrows start again from the newest entry
+    fn remember(&mut self) {
+        let range = self.trimmed_range();
+        self.history.push(&self.buffer[range]);
+        self.recalled = None;
+    }
+
+    // Replaces the line with a history entry, or with the draft past the newest one
+    fn recall(&mut self, idx: usize) {
+        if self.recalled.is_none() {
+            self.draft[..self.len].copy_from_slice(&self.buffer[..self.len]);
+            self.draft_len = self.len;
+        }
+        let line = self
+            .history
+            .get(idx)
+            .unwrap_or(&self.draft[..self.draft_len]);
+        self.buffer[..line.len()].copy_from_slice(line);
+        self.len = line.len();
+        self.recalled = (idx < self.history.len()).then_some(idx);
     }
 }
 
 pub struct Shell {
     screen_idx: usize,
-    commands: [CommandBuffer; VGA_SCREENS],
+    // None for closed terminals and the special ones, which take no input
+    commands: [Option<CommandBuffer>; TERMINAL_SLOTS],
+    // Scripts running inside each other
+    sourcing: usize,
+    // Shared by the screens, so the rc script sets the same ones on each
+    aliases: Aliases,
 }
 
 impl Shell {
     pub fn init(&mut self) {
-        for i in (0..VGA_SCREENS).rev() {
-            // TODO: don't write to vga_buffer for screens 1..VGA_SCREENS
-            self.screen_idx = i;
-            WRITER.lock().switch_screen(i, 0);
-            self.print_welcome();
-            WRITER.lock().write_bytes(b'\n', 2);
-            self.print_prompt();
-            WRITER.lock().reset_history();
+        events::subscribe_decoded_keys(|key, modifiers| SHELL.lock().send_key(key, modifiers))
+            .unwrap();
+        events::subscribe_key_events(|event, modifiers| {
+            let code = event.code();
+            if event.state() != KeyState::Down {
+                return;
+            }
+            let screen_idx = match code {
+                _ if modifiers.is_alt() && (KeyCode::F1..=KeyCode::F12).contains(&code) => {
+                    (code as u8 - KeyCode::F1 as u8) as usize
+                }
+                KeyCode::F11 => WARNING_TERMINAL,
+                KeyCode::F12 => DEBUG_TERMINAL,
+                _ => return,
+            };
+            SHELL.lock().switch_screen(screen_idx);
+        })
+        .unwrap();
+        self.attach(0);
+        for idx in 1..INITIAL_TERMINALS {
+            self.open_terminal(idx);
+        }
+        // Each screen is shown while its rc script runs, ending on the first one
+        for idx in (0..INITIAL_TERMINALS).rev() {
+            self.switch_screen(idx);
         }
+        self.start();
     }
 
-    pub fn send_key(&mut self, key: DecodedKey) {
-        let screen_idx = self.screen_idx;
-        let start_len = self.commands[screen_idx].len;
-        let start_pos = self.commands[screen_idx].pos;
+    // The prompt comes with the rc script, when the screen is first shown
+    fn attach(&mut self, idx: usize) {
+        let color = PROMPT_COLORS[idx.rem_euclid(PROMPT_COLORS.len())];
+        self.commands[idx] = Some(CommandBuffer::new(color));
+        WRITER.lock().with_terminal(idx, |terminal| {
+            Self::print_welcome(terminal, color);
+            terminal.write_repeated(b'\n', 2);
+            terminal.reset_history();
+        });
+    }
+
+    // Commands print on the active terminal, so the rc script of a screen opened later
+    // waits for it to be shown
+    fn start(&mut self) {
+        if !self.has_command() || self.command().started {
+            return;
+        }
+        self.command_mut().started = true;
+        self.run(initrd::find(RC_NAME).unwrap_or(DEFAULT_RC));
+        self.new_prompt();
+    }
+
+    fn open_terminal(&mut self, idx: usize) -> bool {
+        if !WRITER.lock().open_terminal(idx) {
+            return false;
+        }
+        self.attach(idx);
+        log!("tty{}: opened", idx + 1);
+        true
+    }
+
+    fn close_terminal(&mut self, idx: usize) -> bool {
+        if !WRITER.lock().close_terminal(idx) {
+            return false;
+        }
+        self.commands[idx] = None;
+        log!("tty{}: closed", idx + 1);
+        true
+    }
+
+    // Only valid on terminals running a shell
+    const fn command(&self) -> &CommandBuffer {
+        self.commands[self.screen_idx].as_ref().unwrap()
+    }
+
+    const fn command_mut(&mut self) -> &mut CommandBuffer {
+        self.commands[self.screen_idx].as_mut().unwrap()
+    }
+
+    const fn has_command(&self) -> bool {
+        self.commands[self.screen_idx].is_some()
+    }
+
+    // The kernel log and the special screens only scroll, shells scroll with Shift
+    pub fn send_key(&mut self, key: DecodedKey, modifiers: &Modifiers) {
+        let mut writer = WRITER.lock();
+        if writer.in_graphics() {
+            writer.leave_graphics();
+            return;
+        }
+        drop(writer);
+        let scrolls = modifiers.is_shifted() || !self.has_command();
+        match key {
+            DecodedKey::RawKey(KeyCode::ArrowUp) if scrolls => WRITER.lock().move_up(),
+            DecodedKey::RawKey(KeyCode::ArrowDown) if scrolls => WRITER.lock().move_down(),
+            DecodedKey::RawKey(KeyCode::PageUp) => WRITER.lock().move_all_the_way_up(),
+            DecodedKey::RawKey(KeyCode::PageDown) => WRITER.lock().move_all_the_way_down(),
+            DecodedKey::Unicode(character) if self.has_command() && modifiers.is_alt() => {
+                self.edit_with_alt(character);
+            }
+            _ if self.has_command() => self.edit_command(key),
+            _ => {}
+        }
+    }
+
+    fn edit_command(&mut self, key: DecodedKey) {
+        if self.command().search.is_some() && self.edit_search(key) {
+            return;
+        }
+        let start_len = self.command().len;
+        let start_pos = self.command().pos;
+        let tabbed = core::mem::replace(&mut self.command_mut().tabbed, false);
         match key {
             DecodedKey::Unicode(character) => match character {
                 special_char::NEWLINE => {
                     WRITER.lock().write_byte(b'\n');
-                    self.execute_command();
-                    self.print_prompt();
-                    self.commands[screen_idx].len = 0;
-                    self.commands[screen_idx].set_pos(0);
+                    let command = self.command_mut();
+                    command.remember();
+                    let range = command.trimmed_range();
+                    let mut line = [0; MAX_COMMAND_LEN];
+                    line[..range.len()].copy_from_slice(&command.buffer[range.clone()]);
+                    self.run(&line[..range.len()]);
+                    self.new_prompt();
+                }
+                special_char::INTERRUPT => {
+                    WRITER.lock().write_bytes(b"^C\n");
+                    let command = self.command_mut();
+                    command.recalled = None;
+                    command.status = INTERRUPTED_STATUS;
+                    self.new_prompt();
                 }
                 special_char::BACKSPACE => {
-                    if self.commands[screen_idx].pos > 0 {
-                        self.delete_char(screen_idx, true);
+                    if start_pos > 0 {
+                        self.delete_range(start_pos - 1..start_pos, false);
+                    }
+                }
+                special_char::LINE_START => self.command_mut().set_pos(0),
+                special_char::LINE_END => self.command_mut().set_pos(start_len),
+                special_char::BACKWARD => self.command_mut().move_left(),
+                special_char::FORWARD => self.command_mut().move_right(),
+                special_char::KILL_TO_END => self.delete_range(start_pos..start_len, true),
+                special_char::KILL_TO_START => self.delete_range(0..start_pos, true),
+                special_char::KILL_WORD => {
+                    let start = self.command().word_start(|&byte| byte != b' ');
+                    self.delete_range(start..start_pos, true);
+                }
+                special_char::YANK => {
+                    let command = self.command();
+                    let (killed, killed_len) = (command.killed, command.killed_len);
+                    for &byte in &killed[..killed_len] {
+                        self.insert_char(byte);
                     }
                 }
+                special_char::CLEAR_SCREEN => {
+                    WRITER.lock().clear_screen();
+                    self.redraw_command();
+                    self.command_mut().set_pos(start_pos);
+                }
+                special_char::TAB => self.complete(tabbed),
                 special_char::ESCAPE => exit_qemu(QemuExitCode::Success),
+                special_char::REVERSE_SEARCH => {
+                    self.command_mut().search = Some(Search {
+                        query: [0; MAX_COMMAND_LEN],
+                        len: 0,
+                        found: None,
+                    });
+                    self.draw_search();
+                }
                 special_char::DELETE => {
                     if start_pos < start_len {
-                        self.delete_char(screen_idx, false);
-                    }
-                }
-                '\x20'..='\x7e' => {
-                    if start_len < MAX_COMMAND_LEN {
-                        let command = &mut self.commands[screen_idx];
-                        for i in (start_pos..start_len).rev() {
-                            command.buffer[i + 1] = command.buffer[i];
-                        }
-                        command.buffer[start_pos] = character as u8;
-                        WRITER.lock().set_cursor(PROMPT.len() + command.pos);
-                        command.len += 1;
-                        for i in command.pos..command.len {
-                            WRITER.lock().write_byte(command.buffer[i]);
-                        }
-                        command.set_pos(command.pos + 1);
+                        self.delete_range(start_pos..start_pos + 1, false);
                     }
                 }
+                '\x20'..='\x7e' => self.insert_char(character as u8),
                 _ => {}
             },
             DecodedKey::RawKey(k) => match k {
-                KeyCode::ArrowLeft => self.commands[screen_idx].move_left(),
-                KeyCode::ArrowRight => self.commands[screen_idx].move_right(),
-                KeyCode::Home => self.commands[screen_idx].set_pos(0),
-                KeyCode::End => self.commands[screen_idx].set_pos(start_len),
-                KeyCode::ArrowUp => WRITER.lock().move_up(),
-                KeyCode::ArrowDown => WRITER.lock().move_down(),
-                KeyCode::PageUp => WRITER.lock().move_all_the_way_up(),
-                KeyCode::PageDown => WRITER.lock().move_all_the_way_down(),
-                k if (KeyCode::F1..=KeyCode::F4).contains(&k) => {
-                    self.switch_screen((k as u8 - KeyCode::F1 as u8) as usize);
+                KeyCode::ArrowLeft => self.command_mut().move_left(),
+                KeyCode::ArrowRight => self.command_mut().move_right(),
+                KeyCode::ArrowUp => {
+                    let command = self.command();
+                    let current = command.recalled.unwrap_or_else(|| command.history.len());
+                    if current > 0 {
+                        self.recall(current - 1);
+                    }
+                }
+                KeyCode::ArrowDown => {
+                    if let Some(current) = self.command().recalled {
+                        self.recall(current + 1);
+                    }
+                }
+                KeyCode::Home => self.command_mut().set_pos(0),
+                KeyCode::End => self.command_mut().set_pos(start_len),
+                KeyCode::Insert => {
+                    let command = self.command_mut();
+                    command.overwrite = !command.overwrite;
+                    WRITER.lock().set_cursor_shape(command.shown_cursor_shape());
                 }
                 _ => {}
             },
         }
     }
 
+    fn edit_with_alt(&mut self, character: char) {
+        if self.command().search.is_some() {
+            self.accept_search();
+        }
+        self.command_mut().tabbed = false;
+        match character {
+            'b' | 'B' => {
+                let start = self.command().word_start(u8::is_ascii_alphanumeric);
+                self.command_mut().set_pos(start);
+            }
+            'f' | 'F' => {
+                let end = self.command().word_end();
+                self.command_mut().set_pos(end);
+            }
+            special_char::BACKSPACE => {
+                let command = self.command();
+                let start = command.word_start(u8::is_ascii_alphanumeric);
+                self.delete_range(start..command.pos, true);
+            }
+            _ => {}
+        }
+    }
+
+    // After a command or Ctrl+C, in red when it failed
+    fn new_prompt(&mut self) {
+        let shown = self.command();
+        let color = shown.prompt_color();
+        WRITER.lock().with_terminal(self.screen_idx, |terminal| {
+            Self::print_prompt(terminal, color, shown.prompt());
+        });
+        let command = self.command_mut();
+        command.len = 0;
+        command.scroll = 0;
+        command.set_pos(0);
+    }
+
+    // Command names first, then the words given by the completer of the command.
+    // Words are only split on spaces, quotes aren't taken into account.
+    fn complete(&mut self, tabbed: bool) {
+        let command = self.command();
+        let line = &command.buffer[..command.pos];
+        let start = line
+            .iter()
+            .rposition(|&byte| byte == b' ')
+            .map_or(0, |space| space + 1);
+        let prefix = &line[start..];
+        let mut words = line[..start]
+            .split(|&byte| byte == b' ')
+            .filter(|word| !word.is_empty());
+        let command_name = words.next();
+        let names = if command_name.is_none() {
+            COMMAND_HANDLERS
+        } else {
+            &[]
+        };
+        let arguments = command_name
+            .and_then(|name| COMMAND_HANDLERS.iter().find(|handler| handler.name == name))
+            .and_then(|handler| handler.completer)
+            .map_or(&[][..], |completer| completer(words.count()));
+        let candidates = names
+            .iter()
+            .map(|handler| handler.name)
+            .chain(arguments.iter().copied())
+            .filter(|word| word.starts_with(prefix));
+        let mut others = candidates.clone();
+        let Some(first) = others.next() else {
+            return;
+        };
+        let (unique, common) = others.fold((true, first.len()), |(_, len), word| {
+            let shared = first
+                .iter()
+                .zip(word)
+                .take_while(|&(left, right)| left == right);
+            (false, shared.count().min(len))
+        });
+        let prefix_len = prefix.len();
+        if unique {
+            for &byte in &first[prefix_len..] {
+                self.insert_char(byte);
+            }
+            self.insert_char(b' ');
+        } else if common > prefix_len {
+            for &byte in &first[prefix_len..common] {
+                self.insert_char(byte);
+            }
+        } else if tabbed {
+            let mut writer = WRITER.lock();
+            writer.write_byte(b'\n');
+            for word in candidates {
+                writer.write_bytes(word);
+                writer.write_bytes(b"  ");
+            }
+            writer.write_byte(b'\n');
+            drop(writer);
+            let pos = self.command().pos;
+            self.redraw_command();
+            self.command_mut().set_pos(pos);
+        }
+        self.command_mut().tabbed = true;
+    }
+
+    fn recall(&mut self, idx: usize) {
+        self.command_mut().recall(idx);
+        self.redraw_command();
+    }
+
+    // Returns false for the keys that leave the search and still apply to the line
+    fn edit_search(&mut self, key: DecodedKey) -> bool {
+        let command = self.command_mut();
+        let history = &command.history;
+        let Some(search) = command.search.as_mut() else {
+            return false;
+        };
+        match key {
+            DecodedKey::Unicode(special_char::REVERSE_SEARCH) => {
+                let before = search.found.unwrap_or_else(|| history.len());
+                if let Some(older) = history.search(&search.query[..search.len], before) {
+                    search.found = Some(older);
+                }
+            }
+            DecodedKey::Unicode(special_char::BACKSPACE) => {
+                search.len = search.len.saturating_sub(1);
+                search.found = history.search(&search.query[..search.len], history.len());
+            }
+            DecodedKey::Unicode(character @ '\x20'..='\x7e') if search.len < MAX_COMMAND_LEN => {
+                search.query[search.len] = character as u8;
+                search.len += 1;
+                // The current match stays if it still contains the query
+                let before = search.found.map_or_else(|| history.len(), |idx| idx + 1);
+                search.found = history.search(&search.query[..search.len], before);
+            }
+            DecodedKey::Unicode(special_char::CANCEL) => {
+                command.search = None;
+                self.redraw_command();
+                return true;
+            }
+            _ => {
+                self.accept_search();
+                return false;
+            }
+        }
+        self.draw_search();
+        true
+    }
+
+    // The match becomes the line, as if recalled with the arrows
+    fn accept_search(&mut self) {
+        let command = self.command_mut();
+        if let Some(idx) = command.search.take().and_then(|search| search.found) {
+            command.recall(idx);
+        }
+        self.redraw_command();
+    }
+
+    // Rewrites the line of the prompt, what doesn't fit the width is cut
+    fn redraw_line<F: FnOnce(&mut VirtualTerminal, usize)>(&self, draw: F) {
+        WRITER.lock().with_terminal(self.screen_idx, |terminal| {
+            let width = terminal.cols() - 1;
+            terminal.set_cursor(0);
+            terminal.write_repeated(b' ', width);
+            terminal.set_cursor(0);
+            draw(terminal, width);
+        });
+    }
+
+    fn redraw_command(&mut self) {
+        let command = self.command();
+        let color = command.prompt_color();
+        self.redraw_line(|terminal, _| Self::print_prompt(terminal, color, command.prompt()));
+        let len = command.len;
+        self.command_mut().set_pos(len);
+    }
+
+    fn draw_search(&self) {
+        let command = self.command();
+        let Some(search) = command.search.as_ref() else {
+            return;
+        };
+        let query = &search.query[..search.len];
+        let found = search.found.and_then(|idx| command.history.get(idx));
+        let prompt = if found.is_none() && !query.is_empty() {
+            FAILED_SEARCH_PROMPT
+        } else {
+            SEARCH_PROMPT
+        };
+        self.redraw_line(|terminal, width| {
+            let mut remaining = width;
+            for part in [prompt, query, SEARCH_SEPARATOR, found.unwrap_or_default()] {
+                let shown = &part[..part.len().min(remaining)];
+                terminal.write_bytes(shown);
+                remaining -= shown.len();
+            }
+            terminal.set_cursor((prompt.len() + query.len()).min(width));
+        });
+    }
+
+    pub fn send_mouse(&mut self, event: MouseEvent) {
+        let (row, col) = (event.row(), event.col());
+        if event.was_pressed(MouseButton::Left) {
+            WRITER.lock().start_selection(row, col);
+        } else if event.is_down(MouseButton::Left) && event.moved() {
+            WRITER.lock().extend_selection(row, col);
+        } else if event.was_released(MouseButton::Left) {
+            let mut clipboard = CLIPBOARD.lock();
+            WRITER.lock().copy_selection(&mut clipboard);
+        }
+        if (event.was_pressed(MouseButton::Right) || event.was_pressed(MouseButton::Middle))
+            && self.has_command()
+        {
+            self.paste();
+        }
+        WRITER.lock().set_pointer(row, col);
+    }
+
+    fn paste(&mut self) {
+        if self.command().search.is_some() {
+            self.accept_search();
+        }
+        let clipboard = CLIPBOARD.lock();
+        for &byte in clipboard.as_bytes() {
+            self.insert_char(if byte == b'\n' { b' ' } else { byte });
+        }
+    }
+
+    fn insert_char(&mut self, byte: u8) {
+        let command = self.command_mut();
+        if command.overwrite && command.pos < command.len && (0x20..=0x7e).contains(&byte) {
+            command.buffer[command.pos] = byte;
+            command.set_pos(command.pos + 1);
+        } else if command.len < MAX_COMMAND_LEN && (0x20..=0x7e).contains(&byte) {
+            for i in (command.pos..command.len).rev() {
+                command.buffer[i + 1] = command.buffer[i];
+            }
+            command.buffer[command.pos] = byte;
+            command.len += 1;
+            command.set_pos(command.pos + 1);
+        }
+    }
+
     fn switch_screen(&mut self, screen_idx: usize) {
-        if screen_idx != self.screen_idx && screen_idx < VGA_SCREENS {
+        if WRITER.lock().switch_screen(screen_idx) {
             self.screen_idx = screen_idx;
-            WRITER
-                .lock()
-                .switch_screen(screen_idx, self.commands[screen_idx].pos + PROMPT.len());
-        }
-    }
-
-    fn print_prompt(&self) {
-        WRITER
-            .lock()
-            .set_foreground_color(self.commands[self.screen_idx].color);
-        for &byte in PROMPT {
-            WRITER.lock().write_byte(byte);
-        }
-        WRITER.lock().reset_foreground_color();
-    }
-
-    fn print_welcome_line(left: u8, left2: u8, middle: u8, right2: u8, right: u8) {
-        WRITER.lock().write_bytes(b' ', WELCOME_MARGIN);
-        WRITER.lock().write_byte(left);
-        WRITER.lock().write_bytes(left2, CORNER_REPEAT);
-        WRITER
-            .lock()
-            .write_bytes(middle, VGA_WIDTH - 2 * (WELCOME_MARGIN + CORNER_REPEAT + 1));
-        WRITER.lock().write_bytes(right2, CORNER_REPEAT);
-        WRITER.lock().write_byte(right);
-        WRITER.lock().write_bytes(b' ', WELCOME_MARGIN);
-    }
-
-    fn print_welcome_title(s: &'static [u8]) {
-        let remaining_width = VGA_WIDTH - 2 - 2 * WELCOME_MARGIN - s.len();
-        WRITER.lock().write_bytes(b' ', WELCOME_MARGIN);
-        WRITER.lock().write_byte(b'\xba');
-        WRITER.lock().write_bytes(b' ', remaining_width >> 1);
-        for &b in s {
-            WRITER.lock().write_byte(b);
-        }
-        WRITER.lock().write_bytes(b' ', (remaining_width + 1) >> 1);
-        WRITER.lock().write_byte(b'\xba');
-        WRITER.lock().write_bytes(b' ', WELCOME_MARGIN);
-    }
-
-    fn print_welcome(&self) {
-        WRITER
-            .lock()
-            .set_foreground_color(self.commands[self.screen_idx].color);
-        Self::print_welcome_line(b' ', b'\xc9', b'\xcd', b'\xbb', b' ');
-        Self::print_welcome_line(b'\xc9', b'\xbc', b' ', b'\xc8', b'\xbb');
-        Self::print_welcome_title(b"\x20\x20\x20\x20\x20\x20\x3a\x3a\x3a\x20\x20\x20\x20\x3a\x3a\x3a\x20\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x20\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x3a\x3a\x3a\x20\x20\x20\x20\x20\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a");
-        Self::print_welcome_title(b"\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x3a\x2b\x3a\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x3a\x2b\x3a");
-        Self::print_welcome_title(b"\x20\x20\x20\x20\x2b\x3a\x2b\x20\x20\x2b\x3a\x2b\x20\x20\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x3a\x2b\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x3a\x2b\x20\x20");
-        Self::print_welcome_title(b"\x20\x20\x20\x2b\x23\x2b\x2b\x3a\x2b\x2b\x20\x20\x20\x20\x3a\x23\x3a\x3a\x2b\x3a\x3a\x23\x20\x20\x2b\x23\x2b\x2b\x3a\x2b\x2b\x23\x2b\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x20\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20");
-        Self::print_welcome_title(b"\x20\x20\x2b\x23\x2b\x20\x20\x2b\x23\x2b\x20\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x23\x2b\x23\x2b\x23\x2b\x23\x2b\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20\x20\x20\x20");
-        Self::print_welcome_title(b"\x20\x23\x2b\x23\x20\x20\x20\x23\x2b\x23\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20");
-        Self::print_welcome_title(b"\x23\x23\x23\x20\x20\x20\x20\x23\x23\x23\x20\x23\x23\x23\x20\x20\x20\x20\x20\x20\x20\x20\x23\x23\x23\x23\x23\x23\x23\x23\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x23\x23\x23\x20\x20\x23\x23\x23\x23\x23\x23\x23\x23\x23\x23\x20\x20\x20\x20\x20");
-        Self::print_welcome_line(b'\xc8', b'\xbb', b' ', b'\xc9', b'\xbc');
-        Self::print_welcome_line(b' ', b'\xc8', b'\xcd', b'\xbc', b' ');
-        WRITER.lock().reset_foreground_color();
-    }
-
-    fn delete_char(&mut self, screen_idx: usize, decrement_pos: bool) {
-        let command = &mut self.commands[screen_idx];
-        command.len -= 1;
-        if decrement_pos {
-            command.pos -= 1;
-        }
-        for i in command.pos..command.len {
-            command.buffer[i] = command.buffer[i + 1];
-        }
-        WRITER.lock().set_cursor(PROMPT.len() + command.pos);
-        for i in command.pos..command.len {
-            WRITER.lock().write_byte(command.buffer[i]);
-        }
-        WRITER.lock().write_byte(b' ');
-        WRITER.lock().set_cursor(PROMPT.len() + command.pos);
-    }
-
-    fn execute_command(&self) {
-        // TODO: split args (right now it's just a single command)
-        let command_buffer = self.commands[self.screen_idx].trimmed();
-        if command_buffer.is_empty() {
+            self.start();
+        }
+    }
+
+    fn print_prompt(terminal: &mut VirtualTerminal, color: Color, prompt: &[u8]) {
+        terminal.with_foreground_color(color, |writer| writer.write_bytes(prompt));
+    }
+
+    fn print_welcome_line(
+        writer: &mut VirtualTerminal,
+        left: u8,
+        left2: u8,
+        middle: u8,
+        right2: u8,
+        right: u8,
+    ) {
+        writer.write_repeated(b' ', WELCOME_MARGIN);
+        writer.write_byte(left);
+        writer.write_repeated(left2, CORNER_REPEAT);
+        let middle_width = writer
+            .cols()
+            .saturating_sub(2 * (WELCOME_MARGIN + CORNER_REPEAT + 1));
+        writer.write_repeated(middle, middle_width);
+        writer.write_repeated(right2, CORNER_REPEAT);
+        writer.write_byte(right);
+        writer.write_repeated(b' ', WELCOME_MARGIN);
+    }
+
+    fn print_welcome_title(writer: &mut VirtualTerminal, s: &'static [u8]) {
+        let remaining_width = writer
+            .cols()
+            .saturating_sub(2 + 2 * WELCOME_MARGIN + s.len());
+        writer.write_repeated(b' ', WELCOME_MARGIN);
+        writer.write_byte(b'\xba');
+        writer.write_repeated(b' ', remaining_width >> 1);
+        writer.write_bytes(s);
+        writer.write_repeated(b' ', (remaining_width + 1) >> 1);
+        writer.write_byte(b'\xba');
+        writer.write_repeated(b' ', WELCOME_MARGIN);
+    }
+
+    fn print_welcome(terminal: &mut VirtualTerminal, color: Color) {
+        terminal.with_foreground_color(color, |writer| {
+            Self::print_welcome_line(writer, b' ', b'\xc9', b'\xcd', b'\xbb', b' ');
+            Self::print_welcome_line(writer, b'\xc9', b'\xbc', b' ', b'\xc8', b'\xbb');
+            Self::print_welcome_title(writer, b"\x20\x20\x20\x20\x20\x20\x3a\x3a\x3a\x20\x20\x20\x20\x3a\x3a\x3a\x20\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x20\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x3a\x3a\x3a\x20\x20\x20\x20\x20\x3a\x3a\x3a\x3a\x3a\x3a\x3a\x3a");
+            Self::print_welcome_title(writer, b"\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x3a\x2b\x3a\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x20\x3a\x2b\x3a\x20\x20\x20\x20\x3a\x2b\x3a");
+            Self::print_welcome_title(writer, b"\x20\x20\x20\x20\x2b\x3a\x2b\x20\x20\x2b\x3a\x2b\x20\x20\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x3a\x2b\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x3a\x2b\x20\x20");
+            Self::print_welcome_title(writer, b"\x20\x20\x20\x2b\x23\x2b\x2b\x3a\x2b\x2b\x20\x20\x20\x20\x3a\x23\x3a\x3a\x2b\x3a\x3a\x23\x20\x20\x2b\x23\x2b\x2b\x3a\x2b\x2b\x23\x2b\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x20\x20\x2b\x3a\x2b\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20");
+            Self::print_welcome_title(writer, b"\x20\x20\x2b\x23\x2b\x20\x20\x2b\x23\x2b\x20\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20\x20\x20\x2b\x23\x2b\x23\x2b\x23\x2b\x23\x2b\x23\x2b\x20\x20\x2b\x23\x2b\x20\x20\x20\x20\x20\x20\x20\x20");
+            Self::print_welcome_title(writer, b"\x20\x23\x2b\x23\x20\x20\x20\x23\x2b\x23\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x23\x2b\x23\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20");
+            Self::print_welcome_title(writer, b"\x23\x23\x23\x20\x20\x20\x20\x23\x23\x23\x20\x23\x23\x23\x20\x20\x20\x20\x20\x20\x20\x20\x23\x23\x23\x23\x23\x23\x23\x23\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x20\x23\x23\x23\x20\x20\x23\x23\x23\x23\x23\x23\x23\x23\x23\x23\x20\x20\x20\x20\x20");
+            Self::print_welcome_line(writer, b'\xc8', b'\xbb', b' ', b'\xc9', b'\xbc');
+            Self::print_welcome_line(writer, b' ', b'\xc8', b'\xcd', b'\xbc', b' ');
+        });
+    }
+
+    // Killed bytes can be yanked back with Ctrl+Y
+    fn delete_range(&mut self, range: Range<usize>, kill: bool) {
+        let command = self.command_mut();
+        let removed = range.len();
+        if removed == 0 {
             return;
         }
+        if kill {
+            command.killed[..removed].copy_from_slice(&command.buffer[range.clone()]);
+            command.killed_len = removed;
+        }
+        command
+            .buffer
+            .copy_within(range.end..command.len, range.start);
+        command.len -= removed;
+        command.set_pos(range.start);
+    }
 
-        if let Some(handler) = COMMAND_HANDLERS
+    // A command line or a script, a syntax error stops it
+    fn run(&mut self, source: &[u8]) {
+        if let Err(error) = script::run(self, source) {
+            println!("kfs: {}", error.message());
+            self.command_mut().status = PARSE_ERROR_STATUS;
+        }
+    }
+
+    // NAME=value words only set variables
+    fn assign(&mut self, argv: &Argv) -> u8 {
+        for word in argv.iter() {
+            let result = variables::split_assignment(word)
+                .ok_or(variables::Error::InvalidName)
+                .and_then(|(name, value)| self.command_mut().variables.set(name, value));
+            if let Err(error) = result {
+                println!("kfs: {}", error.message());
+                return 1;
+            }
+        }
+        0
+    }
+
+    // Runs the line with its first word replaced when it's an alias, which is hidden
+    // meanwhile so that alias ls='ls -l' runs the command. Quoted words aren't aliases.
+    fn expand_alias(&mut self, line: &[u8]) -> bool {
+        let word_end = line
             .iter()
-            .find(|handler| handler.name == command_buffer)
-        {
-            (handler.handler)(self);
-        } else {
-            println!(
-                "kfs: command not found: \"{}\"",
-                core::str::from_utf8(command_buffer).unwrap_or("invalid utf-8")
-            );
+            .position(|&byte| byte == b' ' || byte == b'\t')
+            .unwrap_or(line.len());
+        let (name, rest) = line.split_at(word_end);
+        let Some(value) = self.aliases.get(name) else {
+            return false;
+        };
+        let len = value.len() + rest.len();
+        if len > MAX_EXPANDED_LEN {
+            println!("kfs: {}", argv::Error::TooLong.message());
+            self.command_mut().status = PARSE_ERROR_STATUS;
+            return true;
         }
+        let mut expanded = [0; MAX_EXPANDED_LEN];
+        expanded[..value.len()].copy_from_slice(value);
+        expanded[value.len()..len].copy_from_slice(rest);
+        self.aliases.set_hidden(name, true);
+        self.run(&expanded[..len]);
+        self.aliases.set_hidden(name, false);
+        true
+    }
+
+    // Empty lines keep the previous status
+    fn execute_command(&mut self, line: &[u8]) {
+        if self.expand_alias(line) {
+            return;
+        }
+        let command = self.command();
+        let argv = match Argv::parse(line, &command.variables, command.status) {
+            Ok(argv) => argv,
+            Err(error) => {
+                println!("kfs: {}", error.message());
+                self.command_mut().status = PARSE_ERROR_STATUS;
+                return;
+            }
+        };
+        let Some(name) = argv.get(0) else {
+            return;
+        };
+        if argv.assignments() > 0 {
+            // Commands don't have variables of their own for the ones before them
+            self.command_mut().status = if argv.get(argv.assignments()).is_none() {
+                self.assign(&argv)
+            } else {
+                println!("kfs: assignments before a command are not supported");
+                PARSE_ERROR_STATUS
+            };
+            return;
+        }
+        let command_name = core::str::from_utf8(name).unwrap_or("invalid utf-8");
+        let Some(handler) = COMMAND_HANDLERS.iter().find(|handler| handler.name == name) else {
+            println!("kfs: command not found: \"{command_name}\"");
+            self.command_mut().status = NOT_FOUND_STATUS;
+            return;
+        };
+
+        let status = match (handler.handler)(self, &argv) {
+            Ok(()) => 0,
+            Err(error) => {
+                match error {
+                    Error::Usage(usage) => println!("usage: {command_name} {usage}"),
+                    Error::Failed(message) => println!("{command_name}: {message}"),
+                    Error::Formatted(message) => println!("{command_name}: {message}"),
+                    Error::Status(_) => {}
+                }
+                error.status()
+            }
+        };
+        self.command_mut().status = status;
     }
 }
 
 lazy_static! {
     pub static ref SHELL: Mutex<Shell> = Mutex::new(Shell {
         screen_idx: 0,
-        commands: [
-            CommandBuffer::new(Color::Pink),
-            CommandBuffer::new(Color::LightCyan),
-            CommandBuffer::new(Color::LightRed),
-            CommandBuffer::new(Color::LightGreen),
-        ],
+        commands: [const { None }; TERMINAL_SLOTS],
+        sourcing: 0,
+        aliases: Aliases::new(),
     });
 }
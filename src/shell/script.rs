@@ -0,0 +1,408 @@
+// Command lists and control flow, run without building a tree: blocks are frames on a
+// stack, and loops jump back to the offset of their first line in the source.
+use {
+    super::{
+        Shell,
+        argv::{self, Argv},
+        variables,
+    },
+    core::ops::Range,
+};
+
+const MAX_DEPTH: usize = 16;
+// Of each loop, commands run in the keyboard interrupt so nothing else can stop them
+const MAX_ITERATIONS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    Unexpected,
+    Unterminated,
+    TooDeep,
+    TooManyIterations,
+    BadFor,
+    Parse(argv::Error),
+    Variable(variables::Error),
+}
+
+impl Error {
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::Unexpected => "unexpected then, elif, else, fi, do or done",
+            Self::Unterminated => "missing fi or done",
+            Self::TooDeep => "blocks nested too deeply",
+            Self::TooManyIterations => "too many loop iterations",
+            Self::BadFor => "for needs a name and in",
+            Self::Parse(error) => error.message(),
+            Self::Variable(error) => error.message(),
+        }
+    }
+}
+
+// What separates a command from the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connector {
+    Always,
+    And,
+    Or,
+}
+
+impl Connector {
+    const fn allows(self, status: u8) -> bool {
+        match self {
+            Self::Always => true,
+            Self::And => status == 0,
+            Self::Or => status != 0,
+        }
+    }
+}
+
+const fn is_blank(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r')
+}
+
+// Without the blanks around
+fn trim(text: &[u8]) -> Range<usize> {
+    let start = text
+        .iter()
+        .position(|&byte| !is_blank(byte))
+        .unwrap_or(text.len());
+    let end = text
+        .iter()
+        .rposition(|&byte| !is_blank(byte))
+        .map_or(start, |last| last + 1);
+    start..end
+}
+
+struct Segment<'source> {
+    // Of the text in the source
+    start: usize,
+    text: &'source [u8],
+    connector: Connector,
+}
+
+// Splits on newlines, ;, && and || outside quotes, and drops the # comments
+struct Segments<'source> {
+    source: &'source [u8],
+    pos: usize,
+    connector: Connector,
+}
+
+impl<'source> Segments<'source> {
+    const fn new(source: &'source [u8]) -> Self {
+        Self {
+            source,
+            pos: 0,
+            connector: Connector::Always,
+        }
+    }
+
+    const fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+        self.connector = Connector::Always;
+    }
+
+    // The end of the text, the connector after it and where the next segment starts
+    fn find_end(&self) -> (usize, Connector, usize) {
+        let source = self.source;
+        let mut quote = None;
+        let mut word_start = true;
+        let mut comment = None;
+        let mut idx = self.pos;
+        while idx < source.len() {
+            let byte = source[idx];
+            let next = source.get(idx + 1).copied();
+            match (quote, byte) {
+                (None, b';' | b'\n') => {
+                    return (comment.unwrap_or(idx), Connector::Always, idx + 1);
+                }
+                (None, b'&') if next == Some(b'&') => {
+                    return (comment.unwrap_or(idx), Connector::And, idx + 2);
+                }
+                (None, b'|') if next == Some(b'|') => {
+                    return (comment.unwrap_or(idx), Connector::Or, idx + 2);
+                }
+                (None, b'#') if word_start => {
+                    comment = Some(idx);
+                    while idx + 1 < source.len() && source[idx + 1] != b'\n' {
+                        idx += 1;
+                    }
+                }
+                (None | Some(b'"'), b'\\') => idx += 1,
+                (None, b'\'' | b'"') => quote = Some(byte),
+                (Some(open), _) if byte == open => quote = None,
+                _ => {}
+            }
+            word_start = quote.is_none() && is_blank(byte);
+            idx += 1;
+        }
+        (
+            comment.unwrap_or(source.len()),
+            Connector::Always,
+            source.len(),
+        )
+    }
+}
+
+impl<'source> Iterator for Segments<'source> {
+    type Item = Segment<'source>;
+
+    // Empty segments are skipped
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.source.len() {
+            let start = self.pos;
+            let (end, connector, next) = self.find_end();
+            let text = trim(&self.source[start..end]);
+            let segment = Segment {
+                start: start + text.start,
+                text: &self.source[start + text.start..start + text.end],
+                connector: self.connector,
+            };
+            self.pos = next;
+            self.connector = connector;
+            if !segment.text.is_empty() {
+                return Some(segment);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    If,
+    Then,
+    Elif,
+    Else,
+    Fi,
+    While,
+    For,
+    Do,
+    Done,
+}
+
+const KEYWORDS: [(&[u8], Keyword); 9] = [
+    (b"if", Keyword::If),
+    (b"then", Keyword::Then),
+    (b"elif", Keyword::Elif),
+    (b"else", Keyword::Else),
+    (b"fi", Keyword::Fi),
+    (b"while", Keyword::While),
+    (b"for", Keyword::For),
+    (b"do", Keyword::Do),
+    (b"done", Keyword::Done),
+];
+
+// The keyword starting a trimmed text and the offset of what follows it
+fn split_keyword(text: &[u8]) -> Option<(Keyword, usize)> {
+    let word_end = text
+        .iter()
+        .position(|&byte| is_blank(byte))
+        .unwrap_or(text.len());
+    let &(_, keyword) = KEYWORDS
+        .iter()
+        .find(|&&(name, _)| name == &text[..word_end])?;
+    Some((keyword, word_end + trim(&text[word_end..]).start))
+}
+
+// Before then or do, after it, and after the else of an if
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Header,
+    Body,
+    Else,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    keyword: Keyword,
+    phase: Phase,
+    // The enclosing blocks run
+    enabled: bool,
+    // The current part of the block runs
+    running: bool,
+    // A branch of the if ran
+    taken: bool,
+    // Of the first line of a loop, where done jumps back
+    start: usize,
+    // The index of the word of a for loop
+    item: usize,
+    // Of a loop, counted by done
+    iterations: usize,
+}
+
+struct Blocks {
+    frames: [Option<Frame>; MAX_DEPTH],
+    depth: usize,
+}
+
+impl Blocks {
+    const fn new() -> Self {
+        Self {
+            frames: [None; MAX_DEPTH],
+            depth: 0,
+        }
+    }
+
+    fn running(&self) -> bool {
+        self.top().is_none_or(|frame| frame.running)
+    }
+
+    fn top(&self) -> Option<&Frame> {
+        self.frames[..self.depth].last()?.as_ref()
+    }
+
+    fn push(&mut self, keyword: Keyword, start: usize) -> Result<&mut Frame, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        let enabled = self.running();
+        self.depth += 1;
+        Ok(self.frames[self.depth - 1].insert(Frame {
+            keyword,
+            phase: Phase::Header,
+            enabled,
+            running: enabled,
+            taken: false,
+            start,
+            item: 0,
+            iterations: 0,
+        }))
+    }
+
+    const fn pop(&mut self) {
+        self.depth -= 1;
+    }
+
+    // The innermost block, which the keyword must belong to
+    fn expect(&mut self, keywords: &[Keyword], phases: &[Phase]) -> Result<&mut Frame, Error> {
+        self.frames[..self.depth]
+            .last_mut()
+            .and_then(Option::as_mut)
+            .filter(|frame| phases.contains(&frame.phase) && keywords.contains(&frame.keyword))
+            .ok_or(Error::Unexpected)
+    }
+
+    // Done jumps back to the first line of a loop, where its frame is reused
+    fn enter_loop(&mut self, keyword: Keyword, start: usize) -> Result<&mut Frame, Error> {
+        let jumped_back = self
+            .expect(&[keyword], &[Phase::Body])
+            .is_ok_and(|frame| frame.start == start);
+        let frame = if jumped_back {
+            self.expect(&[keyword], &[Phase::Body])?
+        } else {
+            self.push(keyword, start)?
+        };
+        frame.phase = Phase::Header;
+        Ok(frame)
+    }
+}
+
+// Sets the variable of a for loop to its next word, false once they are all done
+fn next_item(shell: &mut Shell, header: &[u8], item: usize) -> Result<bool, Error> {
+    let command = shell.command();
+    let words = Argv::parse(header, &command.variables, command.status).map_err(Error::Parse)?;
+    let (Some(name), Some(b"in")) = (words.get(0), words.get(1)) else {
+        return Err(Error::BadFor);
+    };
+    let Some(value) = words.get(item + 2) else {
+        return Ok(false);
+    };
+    shell
+        .command_mut()
+        .variables
+        .set(name, value)
+        .map_err(Error::Variable)?;
+    Ok(true)
+}
+
+// Commands are separated by newlines, ;, && or ||, and blocks are
+// if ...; then ...; [elif ...; then ...;] [else ...;] fi, while ...; do ...; done
+// and for <name> in <words>; do ...; done
+pub fn run(shell: &mut Shell, source: &[u8]) -> Result<(), Error> {
+    let mut blocks = Blocks::new();
+    let mut segments = Segments::new(source);
+    while let Some(segment) = segments.next() {
+        let status = shell.command().status;
+        let (mut start, mut text) = (segment.start, segment.text);
+        // A keyword can start the rest of the segment too, as in then if or do for
+        let command = loop {
+            let Some((keyword, offset)) = split_keyword(text) else {
+                break text;
+            };
+            let rest = &text[offset..];
+            match keyword {
+                Keyword::If => {
+                    blocks.push(keyword, start)?;
+                }
+                Keyword::While => {
+                    let frame = blocks.enter_loop(keyword, start)?;
+                    frame.running = frame.enabled;
+                }
+                Keyword::Then => {
+                    let frame = blocks.expect(&[Keyword::If], &[Phase::Header])?;
+                    frame.running = frame.enabled && !frame.taken && status == 0;
+                    frame.taken |= frame.running;
+                    frame.phase = Phase::Body;
+                }
+                Keyword::Elif => {
+                    let frame = blocks.expect(&[Keyword::If], &[Phase::Body])?;
+                    frame.running = frame.enabled && !frame.taken;
+                    frame.phase = Phase::Header;
+                }
+                Keyword::Else => {
+                    let frame = blocks.expect(&[Keyword::If], &[Phase::Body])?;
+                    frame.running = frame.enabled && !frame.taken;
+                    frame.taken = true;
+                    frame.phase = Phase::Else;
+                }
+                Keyword::Do => {
+                    let frame = blocks.expect(&[Keyword::While, Keyword::For], &[Phase::Header])?;
+                    if frame.keyword == Keyword::While {
+                        frame.running = frame.running && status == 0;
+                    }
+                    frame.phase = Phase::Body;
+                }
+                Keyword::For => {
+                    let frame = blocks.enter_loop(keyword, start)?;
+                    // The first word is taken on the way in, the next ones after each done
+                    frame.item = frame.iterations;
+                    frame.running = frame.enabled && next_item(shell, rest, frame.item)?;
+                    break &[];
+                }
+                Keyword::Fi => {
+                    blocks.expect(&[Keyword::If], &[Phase::Body, Phase::Else])?;
+                    if !rest.is_empty() {
+                        return Err(Error::Unexpected);
+                    }
+                    blocks.pop();
+                    break &[];
+                }
+                Keyword::Done => {
+                    let frame = blocks.expect(&[Keyword::While, Keyword::For], &[Phase::Body])?;
+                    if !rest.is_empty() {
+                        return Err(Error::Unexpected);
+                    }
+                    if !frame.running {
+                        blocks.pop();
+                        break &[];
+                    }
+                    frame.iterations += 1;
+                    if frame.iterations > MAX_ITERATIONS {
+                        return Err(Error::TooManyIterations);
+                    }
+                    segments.seek(frame.start);
+                    break &[];
+                }
+            }
+            start += offset;
+            text = rest;
+        };
+        if !command.is_empty() && blocks.running() && segment.connector.allows(status) {
+            shell.execute_command(command);
+        }
+    }
+    if blocks.depth > 0 {
+        return Err(Error::Unterminated);
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+const MAX_ENTRIES: usize = 16;
+const MAX_NAME_LEN: usize = 16;
+const MAX_VARIABLE_LEN: usize = 64;
+// An alias is a whole command line
+const MAX_ALIAS_LEN: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    InvalidName,
+    TooLong,
+    TooMany,
+}
+
+impl Error {
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::InvalidName => "invalid name",
+            Self::TooLong => "value too long",
+            Self::TooMany => "too many names",
+        }
+    }
+}
+
+pub const fn is_name_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_'
+}
+
+pub const fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+pub fn is_name(bytes: &[u8]) -> bool {
+    bytes.first().is_some_and(|&first| is_name_start(first))
+        && bytes.iter().all(|&byte| is_name_byte(byte))
+}
+
+// NAME=value, the value can be empty
+pub fn split_assignment(word: &[u8]) -> Option<(&[u8], &[u8])> {
+    let equal = word.iter().position(|&byte| byte == b'=')?;
+    is_name(&word[..equal]).then(|| (&word[..equal], &word[equal + 1..]))
+}
+
+#[derive(Clone, Copy)]
+struct Entry<const VALUE_LEN: usize> {
+    name: [u8; MAX_NAME_LEN],
+    name_len: usize,
+    value: [u8; VALUE_LEN],
+    value_len: usize,
+    hidden: bool,
+}
+
+impl<const VALUE_LEN: usize> Entry<VALUE_LEN> {
+    fn name(&self) -> &[u8] {
+        &self.name[..self.name_len]
+    }
+
+    fn value(&self) -> &[u8] {
+        &self.value[..self.value_len]
+    }
+}
+
+// Names and their values, for the variables of a screen or the aliases
+pub struct Table<const VALUE_LEN: usize> {
+    slots: [Option<Entry<VALUE_LEN>>; MAX_ENTRIES],
+}
+
+// Expanded by $NAME and ${NAME}
+pub type Variables = Table<MAX_VARIABLE_LEN>;
+// Replace the first word of a command
+pub type Aliases = Table<MAX_ALIAS_LEN>;
+
+impl<const VALUE_LEN: usize> Table<VALUE_LEN> {
+    pub const fn new() -> Self {
+        Self {
+            slots: [None; MAX_ENTRIES],
+        }
+    }
+
+    fn position(&self, name: &[u8]) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(|entry| entry.name() == name))
+    }
+
+    // Hidden entries aren't found
+    pub fn get(&self, name: &[u8]) -> Option<&[u8]> {
+        self.slots
+            .iter()
+            .flatten()
+            .find(|entry| !entry.hidden && entry.name() == name)
+            .map(Entry::value)
+    }
+
+    pub fn set(&mut self, name: &[u8], value: &[u8]) -> Result<(), Error> {
+        if !is_name(name) {
+            return Err(Error::InvalidName);
+        }
+        if name.len() > MAX_NAME_LEN || value.len() > VALUE_LEN {
+            return Err(Error::TooLong);
+        }
+        let idx = self
+            .position(name)
+            .or_else(|| self.slots.iter().position(Option::is_none))
+            .ok_or(Error::TooMany)?;
+        let mut entry = Entry {
+            name: [0; MAX_NAME_LEN],
+            name_len: name.len(),
+            value: [0; VALUE_LEN],
+            value_len: value.len(),
+            hidden: false,
+        };
+        entry.name[..name.len()].copy_from_slice(name);
+        entry.value[..value.len()].copy_from_slice(value);
+        self.slots[idx] = Some(entry);
+        Ok(())
+    }
+
+    // An alias is hidden while it expands, so that it can't expand itself again
+    pub fn set_hidden(&mut self, name: &[u8], hidden: bool) {
+        if let Some(idx) = self.position(name)
+            && let Some(entry) = self.slots[idx].as_mut()
+        {
+            entry.hidden = hidden;
+        }
+    }
+
+    // False when it wasn't set
+    pub fn unset(&mut self, name: &[u8]) -> bool {
+        self.position(name)
+            .map(|idx| self.slots[idx] = None)
+            .is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|entry| (entry.name(), entry.value()))
+    }
+}
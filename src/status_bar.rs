@@ -0,0 +1,101 @@
+use {
+    crate::{
+        keyboard::KEYBOARD,
+        rtc,
+        vga_buffer::{
+            DEBUG_TERMINAL, LOG_TERMINAL, StatusBar, VGA_MAX_WIDTH, WARNING_TERMINAL, WRITER,
+        },
+    },
+    core::fmt::{self, Write as _},
+};
+
+const REFRESH_MS: u32 = 100;
+
+// What doesn't fit in the line is dropped
+struct LineWriter {
+    bytes: [u8; VGA_MAX_WIDTH],
+    len: usize,
+}
+
+impl LineWriter {
+    const fn new() -> Self {
+        Self {
+            bytes: [b' '; VGA_MAX_WIDTH],
+            len: 0,
+        }
+    }
+}
+
+impl fmt::Write for LineWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            if self.len < VGA_MAX_WIDTH {
+                self.bytes[self.len] = byte;
+                self.len += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+// A refresh finding the keyboard or the writer locked is skipped,
+// the bar keeps its last text until the next one
+pub fn refresh(now: u32) {
+    if now.rem_euclid(REFRESH_MS) != 0 {
+        return;
+    }
+    let Some(keyboard) = KEYBOARD.try_lock() else {
+        return;
+    };
+    let layout = keyboard.layout_name();
+    let capslock = keyboard.modifiers().capslock();
+    let numlock = keyboard.modifiers().numlock();
+    drop(keyboard);
+    let Some(mut writer) = WRITER.try_lock() else {
+        return;
+    };
+    if writer.status_bar() == StatusBar::Hidden {
+        return;
+    }
+
+    let mut left = LineWriter::new();
+    match writer.active() {
+        WARNING_TERMINAL => write!(left, " warnings"),
+        DEBUG_TERMINAL => write!(left, " debug"),
+        LOG_TERMINAL => write!(left, " tty{} (log)", LOG_TERMINAL + 1),
+        idx => write!(left, " tty{}", idx + 1),
+    }
+    .unwrap();
+    write!(
+        left,
+        "  {layout}  {}  {}",
+        if capslock { "CAPS" } else { "caps" },
+        if numlock { "NUM" } else { "num" }
+    )
+    .unwrap();
+
+    let seconds = now.div_euclid(1000);
+    let mut right = LineWriter::new();
+    write!(
+        right,
+        "up {}:{:02}:{:02}",
+        seconds.div_euclid(3600),
+        seconds.div_euclid(60).rem_euclid(60),
+        seconds.rem_euclid(60)
+    )
+    .unwrap();
+    if let Some(time) = rtc::read_time() {
+        write!(
+            right,
+            "  {:02}:{:02}:{:02}",
+            time.hours, time.minutes, time.seconds
+        )
+        .unwrap();
+    }
+    write!(right, " ").unwrap();
+
+    let mut line = left.bytes;
+    let start = writer.width().saturating_sub(right.len);
+    line[start..start + right.len].copy_from_slice(&right.bytes[..right.len]);
+    writer.set_status(&line);
+}
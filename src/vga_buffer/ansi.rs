@@ -0,0 +1,153 @@
+// Subset of the VT100 state machine described at https://vt100.net/emu/dec_ansi_parser
+
+const MAX_PARAMS: usize = 16;
+
+pub const ESCAPE: u8 = 0x1b;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    EscapeIntermediate,
+    ControlSequence,
+    IgnoredControlSequence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    values: [u16; MAX_PARAMS],
+    len: usize,
+}
+
+impl Params {
+    const fn new() -> Self {
+        Self {
+            values: [0; MAX_PARAMS],
+            len: 0,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u16] {
+        &self.values[..self.len]
+    }
+
+    // Missing and zero parameters both mean "use the default"
+    pub fn get_or(&self, idx: usize, default: u16) -> u16 {
+        match self.as_slice().get(idx).copied() {
+            None | Some(0) => default,
+            Some(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Print(u8),
+    Execute(u8),
+    Escape(u8),
+    ControlSequence {
+        private: bool,
+        params: Params,
+        action: u8,
+    },
+}
+
+#[derive(Debug)]
+pub struct Parser {
+    state: State,
+    private: bool,
+    params: Params,
+}
+
+impl Parser {
+    pub const fn new() -> Self {
+        Self {
+            state: State::Ground,
+            private: false,
+            params: Params::new(),
+        }
+    }
+
+    pub fn advance(&mut self, byte: u8) -> Option<Action> {
+        if byte == ESCAPE {
+            self.state = State::Escape;
+            return None;
+        }
+        match self.state {
+            State::Ground => Some(match byte {
+                b'\n' | b'\r' | b'\t' | 0x08 => Action::Execute(byte),
+                _ => Action::Print(byte),
+            }),
+            _ if byte < 0x20 => Some(Action::Execute(byte)),
+            State::Escape => match byte {
+                b'[' => {
+                    self.state = State::ControlSequence;
+                    self.private = false;
+                    self.params = Params::new();
+                    None
+                }
+                0x20..=0x2f => {
+                    self.state = State::EscapeIntermediate;
+                    None
+                }
+                _ => {
+                    self.state = State::Ground;
+                    Some(Action::Escape(byte))
+                }
+            },
+            State::EscapeIntermediate => {
+                if !(0x20..=0x2f).contains(&byte) {
+                    self.state = State::Ground;
+                }
+                None
+            }
+            State::ControlSequence => self.advance_control_sequence(byte),
+            State::IgnoredControlSequence => {
+                if (0x40..=0x7e).contains(&byte) {
+                    self.state = State::Ground;
+                }
+                None
+            }
+        }
+    }
+
+    fn advance_control_sequence(&mut self, byte: u8) -> Option<Action> {
+        match byte {
+            b'0'..=b'9' => {
+                if self.params.len == 0 {
+                    self.params.len = 1;
+                }
+                let value = &mut self.params.values[self.params.len - 1];
+                *value = value
+                    .saturating_mul(10)
+                    .saturating_add(u16::from(byte - b'0'));
+                None
+            }
+            b';' => {
+                if self.params.len == 0 {
+                    self.params.len = 1;
+                }
+                if self.params.len < MAX_PARAMS {
+                    self.params.len += 1;
+                }
+                None
+            }
+            b'<'..=b'?' if self.params.len == 0 && !self.private => {
+                self.private = true;
+                None
+            }
+            0x40..=0x7e => {
+                self.state = State::Ground;
+                Some(Action::ControlSequence {
+                    private: self.private,
+                    params: self.params,
+                    action: byte,
+                })
+            }
+            _ => {
+                self.state = State::IgnoredControlSequence;
+                None
+            }
+        }
+    }
+}
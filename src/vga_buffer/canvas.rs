@@ -0,0 +1,146 @@
+use {
+    super::{PALETTE, mode},
+    core::ops::Range,
+    volatile::Volatile,
+};
+
+pub const WIDTH: usize = 320;
+pub const HEIGHT: usize = 200;
+const PIXELS_ADDRESS: usize = 0xA0000;
+// Color levels of the 6x6x6 cube of the default palette
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x33, 0x66, 0x99, 0xCC, 0xFF];
+const CUBE_START: u8 = 16;
+const GRAY_START: u8 = CUBE_START + 216;
+
+type Pixels = [Volatile<u8>; WIDTH * HEIGHT];
+
+// Index of a color of the cube, each channel goes from 0 to 5
+pub const fn cube(red: u8, green: u8, blue: u8) -> u8 {
+    CUBE_START + 36 * red + 6 * green + blue
+}
+
+// The 16 text colors, a 6x6x6 color cube and 24 grays, like xterm
+pub fn load_default_palette() {
+    for (index, &rgb) in (0..).zip(&PALETTE) {
+        set_color(index, rgb);
+    }
+    for (index, red, green, blue) in (CUBE_START..GRAY_START).map(|index| {
+        let cube_index = index - CUBE_START;
+        (
+            index,
+            cube_index.div_euclid(36),
+            cube_index.div_euclid(6).rem_euclid(6),
+            cube_index.rem_euclid(6),
+        )
+    }) {
+        let [r, g, b] = [red, green, blue].map(|level| u32::from(CUBE_LEVELS[usize::from(level)]));
+        set_color(index, r << 16 | g << 8 | b);
+    }
+    for index in GRAY_START..=u8::MAX {
+        let level = u32::from(8 + 10 * (index - GRAY_START));
+        set_color(index, level << 16 | level << 8 | level);
+    }
+}
+
+// 0xRRGGBB, the DAC only keeps 6 bits per channel
+pub fn set_color(index: u8, rgb: u32) {
+    let channel = |shift: u32| ((rgb >> shift) as u8) >> 2;
+    mode::write_dac(index, [channel(16), channel(8), channel(0)]);
+}
+
+// The part of [start, start + len) that is on a line of max pixels
+fn clip(start: i32, len: i32, max: usize) -> Range<usize> {
+    let bound = |coord: i32| usize::try_from(coord).map_or(0, |unsigned| unsigned.min(max));
+    bound(start)..bound(start.saturating_add(len))
+}
+
+// Mode 13h, one byte per pixel indexing the 256 colors of the DAC.
+// Coordinates are signed so that shapes can stick out of the screen.
+// It borrows the writer, which can't go back to text mode while it exists.
+pub struct Canvas<'writer> {
+    pixels: &'writer mut Pixels,
+}
+
+impl Canvas<'_> {
+    // The VGA must stay in mode 13h for the whole lifetime of the canvas,
+    // and there can't be two canvases at once
+    pub(super) const unsafe fn new() -> Self {
+        Self {
+            pixels: unsafe { &mut *(PIXELS_ADDRESS as *mut Pixels) },
+        }
+    }
+
+    // Out of bounds pixels are ignored
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: u8) {
+        if let (Ok(col), Ok(row)) = (usize::try_from(x), usize::try_from(y))
+            && col < WIDTH
+            && row < HEIGHT
+        {
+            self.pixels[row * WIDTH + col].write(color);
+        }
+    }
+
+    pub fn clear(&mut self, color: u8) {
+        for pixel in self.pixels.iter_mut() {
+            pixel.write(color);
+        }
+    }
+
+    // Bresenham, both ends included
+    pub fn line(&mut self, (x0, y0): (i32, i32), (x1, y1): (i32, i32), color: u8) {
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (step_x, step_y) = ((x1 - x0).signum(), (y1 - y0).signum());
+        let (mut x, mut y, mut error) = (x0, y0, dx + dy);
+        loop {
+            self.put_pixel(x, y, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    pub fn rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u8) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let (right, bottom) = (x + width - 1, y + height - 1);
+        self.line((x, y), (right, y), color);
+        self.line((x, bottom), (right, bottom), color);
+        self.line((x, y), (x, bottom), color);
+        self.line((right, y), (right, bottom), color);
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u8) {
+        let cols = clip(x, width, WIDTH);
+        for row in clip(y, height, HEIGHT) {
+            for pixel in &mut self.pixels[row * WIDTH..][cols.clone()] {
+                pixel.write(color);
+            }
+        }
+    }
+
+    // Copies rows of width pixels, the transparent color leaves the canvas as it was.
+    // A last row shorter than width is left out.
+    pub fn blit(&mut self, x: i32, y: i32, width: usize, pixels: &[u8], transparent: Option<u8>) {
+        if width == 0 {
+            return;
+        }
+        let whole_rows = pixels.len() - pixels.len().rem_euclid(width);
+        for (row_y, row) in (y..).zip(pixels[..whole_rows].chunks(width)) {
+            for (col_x, &color) in (x..).zip(row) {
+                if Some(color) != transparent {
+                    self.put_pixel(col_x, row_y, color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use {
+    super::{
+        CursorShape, PALETTE, ScreenChar, VGA_MAX_HEIGHT, VGA_MAX_WIDTH,
+        font::{self, Font, GLYPH_HEIGHT},
+    },
+    crate::framebuffer::Framebuffer,
+};
+
+const GLYPH_WIDTH: usize = 8;
+
+// Text cells drawn with a bitmap font on a linear framebuffer, for the compositor.
+// Nothing blinks: the high bit of the background always brightens it.
+pub struct FramebufferConsole {
+    framebuffer: Framebuffer,
+    font: Font,
+    colors: [u32; 16],
+    cols: usize,
+    rows: usize,
+}
+
+impl FramebufferConsole {
+    pub fn new(framebuffer: Framebuffer) -> Self {
+        let mut colors = [0; 16];
+        for (pixel, &rgb) in colors.iter_mut().zip(&PALETTE) {
+            *pixel = framebuffer.pixel(rgb);
+        }
+        Self {
+            cols: framebuffer
+                .width()
+                .div_euclid(GLYPH_WIDTH)
+                .min(VGA_MAX_WIDTH),
+            rows: framebuffer
+                .height()
+                .div_euclid(GLYPH_HEIGHT)
+                .min(VGA_MAX_HEIGHT),
+            framebuffer,
+            font: font::default_font(),
+            colors,
+        }
+    }
+
+    pub const fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub const fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn reset_font(&mut self) {
+        self.font = font::default_font();
+    }
+
+    // Same layout as the glyphs uploaded to plane 2, see font::is_glyph_height
+    pub fn set_glyph(&mut self, code: u8, rows: &[u8]) {
+        self.font[usize::from(code)] = font::scale_glyph(rows);
+    }
+
+    pub fn clear(&mut self) {
+        self.framebuffer.clear(self.colors[0]);
+    }
+
+    // The cursor is drawn in the foreground color like the VGA one, over the rows of its shape
+    pub fn draw_cell(
+        &mut self,
+        row: usize,
+        col: usize,
+        sc: ScreenChar,
+        cursor: Option<CursorShape>,
+    ) {
+        let foreground = self.colors[usize::from(sc.color_code.0 & 0x0F)];
+        let background = self.colors[usize::from(sc.color_code.0 >> 4)];
+        let cursor_start = match cursor {
+            None => GLYPH_HEIGHT,
+            Some(CursorShape::Underline) => GLYPH_HEIGHT - 2,
+            Some(CursorShape::Half) => GLYPH_HEIGHT >> 1,
+            Some(CursorShape::Block) => 0,
+        };
+        let glyph = self.font[usize::from(sc.ascii_character)];
+        let (x, y) = (col * GLYPH_WIDTH, row * GLYPH_HEIGHT);
+        for (dy, &glyph_row) in glyph.iter().enumerate() {
+            let bits = if dy >= cursor_start { 0xFF } else { glyph_row };
+            for dx in 0..GLYPH_WIDTH {
+                let pixel = if bits & (0x80 >> dx) != 0 {
+                    foreground
+                } else {
+                    background
+                };
+                self.framebuffer.put_pixel(x + dx, y + dy, pixel);
+            }
+        }
+    }
+}
@@ -0,0 +1,1601 @@
+// Classic 8x8 PC font, the printable ASCII characters come from the public domain font8x8.
+// Each row is a byte with the leftmost pixel in the high bit.
+
+#[rustfmt::skip]
+#[expect(clippy::unreadable_literal)] // the bits are the pixels
+pub const ASCII: [[u8; 8]; 95] = [
+    // space
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // !
+    [
+        0b00011000,
+        0b00111100,
+        0b00111100,
+        0b00011000,
+        0b00011000,
+        0b00000000,
+        0b00011000,
+        0b00000000,
+    ],
+    // "
+    [
+        0b01101100,
+        0b01101100,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // #
+    [
+        0b01101100,
+        0b01101100,
+        0b11111110,
+        0b01101100,
+        0b11111110,
+        0b01101100,
+        0b01101100,
+        0b00000000,
+    ],
+    // $
+    [
+        0b00110000,
+        0b01111100,
+        0b11000000,
+        0b01111000,
+        0b00001100,
+        0b11111000,
+        0b00110000,
+        0b00000000,
+    ],
+    // %
+    [
+        0b00000000,
+        0b11000110,
+        0b11001100,
+        0b00011000,
+        0b00110000,
+        0b01100110,
+        0b11000110,
+        0b00000000,
+    ],
+    // &
+    [
+        0b00111000,
+        0b01101100,
+        0b00111000,
+        0b01110110,
+        0b11011100,
+        0b11001100,
+        0b01110110,
+        0b00000000,
+    ],
+    // '
+    [
+        0b01100000,
+        0b01100000,
+        0b11000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // (
+    [
+        0b00011000,
+        0b00110000,
+        0b01100000,
+        0b01100000,
+        0b01100000,
+        0b00110000,
+        0b00011000,
+        0b00000000,
+    ],
+    // )
+    [
+        0b01100000,
+        0b00110000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00110000,
+        0b01100000,
+        0b00000000,
+    ],
+    // *
+    [
+        0b00000000,
+        0b01100110,
+        0b00111100,
+        0b11111111,
+        0b00111100,
+        0b01100110,
+        0b00000000,
+        0b00000000,
+    ],
+    // +
+    [
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b11111100,
+        0b00110000,
+        0b00110000,
+        0b00000000,
+        0b00000000,
+    ],
+    // ,
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b01100000,
+    ],
+    // -
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111100,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // .
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b00000000,
+    ],
+    // /
+    [
+        0b00000110,
+        0b00001100,
+        0b00011000,
+        0b00110000,
+        0b01100000,
+        0b11000000,
+        0b10000000,
+        0b00000000,
+    ],
+    // 0
+    [
+        0b01111100,
+        0b11000110,
+        0b11001110,
+        0b11011110,
+        0b11110110,
+        0b11100110,
+        0b01111100,
+        0b00000000,
+    ],
+    // 1
+    [
+        0b00110000,
+        0b01110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b11111100,
+        0b00000000,
+    ],
+    // 2
+    [
+        0b01111000,
+        0b11001100,
+        0b00001100,
+        0b00111000,
+        0b01100000,
+        0b11001100,
+        0b11111100,
+        0b00000000,
+    ],
+    // 3
+    [
+        0b01111000,
+        0b11001100,
+        0b00001100,
+        0b00111000,
+        0b00001100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // 4
+    [
+        0b00011100,
+        0b00111100,
+        0b01101100,
+        0b11001100,
+        0b11111110,
+        0b00001100,
+        0b00011110,
+        0b00000000,
+    ],
+    // 5
+    [
+        0b11111100,
+        0b11000000,
+        0b11111000,
+        0b00001100,
+        0b00001100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // 6
+    [
+        0b00111000,
+        0b01100000,
+        0b11000000,
+        0b11111000,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // 7
+    [
+        0b11111100,
+        0b11001100,
+        0b00001100,
+        0b00011000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00000000,
+    ],
+    // 8
+    [
+        0b01111000,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // 9
+    [
+        0b01111000,
+        0b11001100,
+        0b11001100,
+        0b01111100,
+        0b00001100,
+        0b00011000,
+        0b01110000,
+        0b00000000,
+    ],
+    // :
+    [
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b00000000,
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b00000000,
+    ],
+    // ;
+    [
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b00000000,
+        0b00000000,
+        0b00110000,
+        0b00110000,
+        0b01100000,
+    ],
+    // <
+    [
+        0b00011000,
+        0b00110000,
+        0b01100000,
+        0b11000000,
+        0b01100000,
+        0b00110000,
+        0b00011000,
+        0b00000000,
+    ],
+    // =
+    [
+        0b00000000,
+        0b00000000,
+        0b11111100,
+        0b00000000,
+        0b00000000,
+        0b11111100,
+        0b00000000,
+        0b00000000,
+    ],
+    // >
+    [
+        0b01100000,
+        0b00110000,
+        0b00011000,
+        0b00001100,
+        0b00011000,
+        0b00110000,
+        0b01100000,
+        0b00000000,
+    ],
+    // ?
+    [
+        0b01111000,
+        0b11001100,
+        0b00001100,
+        0b00011000,
+        0b00110000,
+        0b00000000,
+        0b00110000,
+        0b00000000,
+    ],
+    // @
+    [
+        0b01111100,
+        0b11000110,
+        0b11011110,
+        0b11011110,
+        0b11011110,
+        0b11000000,
+        0b01111000,
+        0b00000000,
+    ],
+    // A
+    [
+        0b00110000,
+        0b01111000,
+        0b11001100,
+        0b11001100,
+        0b11111100,
+        0b11001100,
+        0b11001100,
+        0b00000000,
+    ],
+    // B
+    [
+        0b11111100,
+        0b01100110,
+        0b01100110,
+        0b01111100,
+        0b01100110,
+        0b01100110,
+        0b11111100,
+        0b00000000,
+    ],
+    // C
+    [
+        0b00111100,
+        0b01100110,
+        0b11000000,
+        0b11000000,
+        0b11000000,
+        0b01100110,
+        0b00111100,
+        0b00000000,
+    ],
+    // D
+    [
+        0b11111000,
+        0b01101100,
+        0b01100110,
+        0b01100110,
+        0b01100110,
+        0b01101100,
+        0b11111000,
+        0b00000000,
+    ],
+    // E
+    [
+        0b11111110,
+        0b01100010,
+        0b01101000,
+        0b01111000,
+        0b01101000,
+        0b01100010,
+        0b11111110,
+        0b00000000,
+    ],
+    // F
+    [
+        0b11111110,
+        0b01100010,
+        0b01101000,
+        0b01111000,
+        0b01101000,
+        0b01100000,
+        0b11110000,
+        0b00000000,
+    ],
+    // G
+    [
+        0b00111100,
+        0b01100110,
+        0b11000000,
+        0b11000000,
+        0b11001110,
+        0b01100110,
+        0b00111110,
+        0b00000000,
+    ],
+    // H
+    [
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11111100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b00000000,
+    ],
+    // I
+    [
+        0b01111000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b01111000,
+        0b00000000,
+    ],
+    // J
+    [
+        0b00011110,
+        0b00001100,
+        0b00001100,
+        0b00001100,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // K
+    [
+        0b11100110,
+        0b01100110,
+        0b01101100,
+        0b01111000,
+        0b01101100,
+        0b01100110,
+        0b11100110,
+        0b00000000,
+    ],
+    // L
+    [
+        0b11110000,
+        0b01100000,
+        0b01100000,
+        0b01100000,
+        0b01100010,
+        0b01100110,
+        0b11111110,
+        0b00000000,
+    ],
+    // M
+    [
+        0b11000110,
+        0b11101110,
+        0b11111110,
+        0b11111110,
+        0b11010110,
+        0b11000110,
+        0b11000110,
+        0b00000000,
+    ],
+    // N
+    [
+        0b11000110,
+        0b11100110,
+        0b11110110,
+        0b11011110,
+        0b11001110,
+        0b11000110,
+        0b11000110,
+        0b00000000,
+    ],
+    // O
+    [
+        0b00111000,
+        0b01101100,
+        0b11000110,
+        0b11000110,
+        0b11000110,
+        0b01101100,
+        0b00111000,
+        0b00000000,
+    ],
+    // P
+    [
+        0b11111100,
+        0b01100110,
+        0b01100110,
+        0b01111100,
+        0b01100000,
+        0b01100000,
+        0b11110000,
+        0b00000000,
+    ],
+    // Q
+    [
+        0b01111000,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11011100,
+        0b01111000,
+        0b00011100,
+        0b00000000,
+    ],
+    // R
+    [
+        0b11111100,
+        0b01100110,
+        0b01100110,
+        0b01111100,
+        0b01101100,
+        0b01100110,
+        0b11100110,
+        0b00000000,
+    ],
+    // S
+    [
+        0b01111000,
+        0b11001100,
+        0b11100000,
+        0b01110000,
+        0b00011100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // T
+    [
+        0b11111100,
+        0b10110100,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b01111000,
+        0b00000000,
+    ],
+    // U
+    [
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11111100,
+        0b00000000,
+    ],
+    // V
+    [
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00110000,
+        0b00000000,
+    ],
+    // W
+    [
+        0b11000110,
+        0b11000110,
+        0b11000110,
+        0b11010110,
+        0b11111110,
+        0b11101110,
+        0b11000110,
+        0b00000000,
+    ],
+    // X
+    [
+        0b11000110,
+        0b11000110,
+        0b01101100,
+        0b00111000,
+        0b00111000,
+        0b01101100,
+        0b11000110,
+        0b00000000,
+    ],
+    // Y
+    [
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00110000,
+        0b00110000,
+        0b01111000,
+        0b00000000,
+    ],
+    // Z
+    [
+        0b11111110,
+        0b11000110,
+        0b10001100,
+        0b00011000,
+        0b00110010,
+        0b01100110,
+        0b11111110,
+        0b00000000,
+    ],
+    // [
+    [
+        0b01111000,
+        0b01100000,
+        0b01100000,
+        0b01100000,
+        0b01100000,
+        0b01100000,
+        0b01111000,
+        0b00000000,
+    ],
+    // \
+    [
+        0b11000000,
+        0b01100000,
+        0b00110000,
+        0b00011000,
+        0b00001100,
+        0b00000110,
+        0b00000010,
+        0b00000000,
+    ],
+    // ]
+    [
+        0b01111000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b01111000,
+        0b00000000,
+    ],
+    // ^
+    [
+        0b00010000,
+        0b00111000,
+        0b01101100,
+        0b11000110,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // _
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111111,
+    ],
+    // `
+    [
+        0b00110000,
+        0b00110000,
+        0b00011000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // a
+    [
+        0b00000000,
+        0b00000000,
+        0b01111000,
+        0b00001100,
+        0b01111100,
+        0b11001100,
+        0b01110110,
+        0b00000000,
+    ],
+    // b
+    [
+        0b11100000,
+        0b01100000,
+        0b01100000,
+        0b01111100,
+        0b01100110,
+        0b01100110,
+        0b11011100,
+        0b00000000,
+    ],
+    // c
+    [
+        0b00000000,
+        0b00000000,
+        0b01111000,
+        0b11001100,
+        0b11000000,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // d
+    [
+        0b00011100,
+        0b00001100,
+        0b00001100,
+        0b01111100,
+        0b11001100,
+        0b11001100,
+        0b01110110,
+        0b00000000,
+    ],
+    // e
+    [
+        0b00000000,
+        0b00000000,
+        0b01111000,
+        0b11001100,
+        0b11111100,
+        0b11000000,
+        0b01111000,
+        0b00000000,
+    ],
+    // f
+    [
+        0b00111000,
+        0b01101100,
+        0b01100000,
+        0b11110000,
+        0b01100000,
+        0b01100000,
+        0b11110000,
+        0b00000000,
+    ],
+    // g
+    [
+        0b00000000,
+        0b00000000,
+        0b01110110,
+        0b11001100,
+        0b11001100,
+        0b01111100,
+        0b00001100,
+        0b11111000,
+    ],
+    // h
+    [
+        0b11100000,
+        0b01100000,
+        0b01101100,
+        0b01110110,
+        0b01100110,
+        0b01100110,
+        0b11100110,
+        0b00000000,
+    ],
+    // i
+    [
+        0b00110000,
+        0b00000000,
+        0b01110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b01111000,
+        0b00000000,
+    ],
+    // j
+    [
+        0b00001100,
+        0b00000000,
+        0b00001100,
+        0b00001100,
+        0b00001100,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+    ],
+    // k
+    [
+        0b11100000,
+        0b01100000,
+        0b01100110,
+        0b01101100,
+        0b01111000,
+        0b01101100,
+        0b11100110,
+        0b00000000,
+    ],
+    // l
+    [
+        0b01110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b00110000,
+        0b01111000,
+        0b00000000,
+    ],
+    // m
+    [
+        0b00000000,
+        0b00000000,
+        0b11001100,
+        0b11111110,
+        0b11111110,
+        0b11010110,
+        0b11000110,
+        0b00000000,
+    ],
+    // n
+    [
+        0b00000000,
+        0b00000000,
+        0b11111000,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b00000000,
+    ],
+    // o
+    [
+        0b00000000,
+        0b00000000,
+        0b01111000,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00000000,
+    ],
+    // p
+    [
+        0b00000000,
+        0b00000000,
+        0b11011100,
+        0b01100110,
+        0b01100110,
+        0b01111100,
+        0b01100000,
+        0b11110000,
+    ],
+    // q
+    [
+        0b00000000,
+        0b00000000,
+        0b01110110,
+        0b11001100,
+        0b11001100,
+        0b01111100,
+        0b00001100,
+        0b00011110,
+    ],
+    // r
+    [
+        0b00000000,
+        0b00000000,
+        0b11011100,
+        0b01110110,
+        0b01100110,
+        0b01100000,
+        0b11110000,
+        0b00000000,
+    ],
+    // s
+    [
+        0b00000000,
+        0b00000000,
+        0b01111100,
+        0b11000000,
+        0b01111000,
+        0b00001100,
+        0b11111000,
+        0b00000000,
+    ],
+    // t
+    [
+        0b00010000,
+        0b00110000,
+        0b01111100,
+        0b00110000,
+        0b00110000,
+        0b00110100,
+        0b00011000,
+        0b00000000,
+    ],
+    // u
+    [
+        0b00000000,
+        0b00000000,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b01110110,
+        0b00000000,
+    ],
+    // v
+    [
+        0b00000000,
+        0b00000000,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b01111000,
+        0b00110000,
+        0b00000000,
+    ],
+    // w
+    [
+        0b00000000,
+        0b00000000,
+        0b11000110,
+        0b11010110,
+        0b11111110,
+        0b11111110,
+        0b01101100,
+        0b00000000,
+    ],
+    // x
+    [
+        0b00000000,
+        0b00000000,
+        0b11000110,
+        0b01101100,
+        0b00111000,
+        0b01101100,
+        0b11000110,
+        0b00000000,
+    ],
+    // y
+    [
+        0b00000000,
+        0b00000000,
+        0b11001100,
+        0b11001100,
+        0b11001100,
+        0b01111100,
+        0b00001100,
+        0b11111000,
+    ],
+    // z
+    [
+        0b00000000,
+        0b00000000,
+        0b11111100,
+        0b10011000,
+        0b00110000,
+        0b01100100,
+        0b11111100,
+        0b00000000,
+    ],
+    // {
+    [
+        0b00011100,
+        0b00110000,
+        0b00110000,
+        0b11100000,
+        0b00110000,
+        0b00110000,
+        0b00011100,
+        0b00000000,
+    ],
+    // |
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00000000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00000000,
+    ],
+    // }
+    [
+        0b11100000,
+        0b00110000,
+        0b00110000,
+        0b00011100,
+        0b00110000,
+        0b00110000,
+        0b11100000,
+        0b00000000,
+    ],
+    // ~
+    [
+        0b01110110,
+        0b11011100,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+];
+
+// Shades, box drawing and blocks from code page 437, the lines reach the edges
+// so that neighbouring cells connect
+#[rustfmt::skip]
+#[expect(clippy::unreadable_literal)]
+pub const GRAPHICS: [[u8; 8]; 48] = [
+    // 0xb0
+    [
+        0b00100010,
+        0b10001000,
+        0b00100010,
+        0b10001000,
+        0b00100010,
+        0b10001000,
+        0b00100010,
+        0b10001000,
+    ],
+    // 0xb1
+    [
+        0b01010101,
+        0b10101010,
+        0b01010101,
+        0b10101010,
+        0b01010101,
+        0b10101010,
+        0b01010101,
+        0b10101010,
+    ],
+    // 0xb2
+    [
+        0b11011101,
+        0b01110111,
+        0b11011101,
+        0b01110111,
+        0b11011101,
+        0b01110111,
+        0b11011101,
+        0b01110111,
+    ],
+    // 0xb3
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xb4
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b11111000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xb5
+    [
+        0b00011000,
+        0b00011000,
+        0b11111000,
+        0b00011000,
+        0b11111000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xb6
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b11110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xb7
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xb8
+    [
+        0b00000000,
+        0b00000000,
+        0b11111000,
+        0b00011000,
+        0b11111000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xb9
+    [
+        0b00110110,
+        0b00110110,
+        0b11110110,
+        0b00000110,
+        0b11110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xba
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xbb
+    [
+        0b00000000,
+        0b00000000,
+        0b11111110,
+        0b00000110,
+        0b11110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xbc
+    [
+        0b00110110,
+        0b00110110,
+        0b11110110,
+        0b00000110,
+        0b11111110,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xbd
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b11111110,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xbe
+    [
+        0b00011000,
+        0b00011000,
+        0b11111000,
+        0b00011000,
+        0b11111000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xbf
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xc0
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xc1
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xc2
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xc3
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xc4
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xc5
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b11111111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xc6
+    [
+        0b00011000,
+        0b00011000,
+        0b00011111,
+        0b00011000,
+        0b00011111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xc7
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xc8
+    [
+        0b00110110,
+        0b00110110,
+        0b00110111,
+        0b00110000,
+        0b00111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xc9
+    [
+        0b00000000,
+        0b00000000,
+        0b00111111,
+        0b00110000,
+        0b00110111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xca
+    [
+        0b00110110,
+        0b00110110,
+        0b11110111,
+        0b00000000,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xcb
+    [
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b00000000,
+        0b11110111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xcc
+    [
+        0b00110110,
+        0b00110110,
+        0b00110111,
+        0b00110000,
+        0b00110111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xcd
+    [
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b00000000,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xce
+    [
+        0b00110110,
+        0b00110110,
+        0b11110111,
+        0b00000000,
+        0b11110111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xcf
+    [
+        0b00011000,
+        0b00011000,
+        0b11111111,
+        0b00011000,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xd0
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xd1
+    [
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b00011000,
+        0b11111111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xd2
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xd3
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xd4
+    [
+        0b00011000,
+        0b00011000,
+        0b00011111,
+        0b00011000,
+        0b00011111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xd5
+    [
+        0b00000000,
+        0b00000000,
+        0b00011111,
+        0b00011000,
+        0b00011111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xd6
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00111111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xd7
+    [
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b11111111,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+        0b00110110,
+    ],
+    // 0xd8
+    [
+        0b00011000,
+        0b00011000,
+        0b11111111,
+        0b00011000,
+        0b11111111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xd9
+    [
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b11111000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+    // 0xda
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00011111,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+        0b00011000,
+    ],
+    // 0xdb
+    [
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+    ],
+    // 0xdc
+    [
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+    ],
+    // 0xdd
+    [
+        0b11110000,
+        0b11110000,
+        0b11110000,
+        0b11110000,
+        0b11110000,
+        0b11110000,
+        0b11110000,
+        0b11110000,
+    ],
+    // 0xde
+    [
+        0b00001111,
+        0b00001111,
+        0b00001111,
+        0b00001111,
+        0b00001111,
+        0b00001111,
+        0b00001111,
+        0b00001111,
+    ],
+    // 0xdf
+    [
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b11111111,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+        0b00000000,
+    ],
+];
+
+// Shown for the characters this font doesn't have
+#[rustfmt::skip]
+#[expect(clippy::unreadable_literal)]
+pub const MISSING: [u8; 8] = [
+    0b00000000,
+    0b01111110,
+    0b01000010,
+    0b01000010,
+    0b01000010,
+    0b01000010,
+    0b01111110,
+    0b00000000,
+];
@@ -0,0 +1,140 @@
+mod kfs;
+
+use {
+    super::{
+        VGA_ADDRESS,
+        mode::{
+            GRAPHICS_ADDRESS, SEQUENCER_ADDRESS, graphics, read_indexed, sequencer, write_indexed,
+        },
+    },
+    volatile::Volatile,
+};
+
+pub const GLYPHS: usize = 256;
+pub const GLYPH_HEIGHT: usize = 16;
+pub const SMALL_GLYPH_HEIGHT: usize = 8;
+const FONT_PLANE: u8 = 2;
+const GLYPH_STRIDE: usize = 32; // bytes reserved for each glyph, whatever its height
+// Block 0 is used by the 16 pixel high modes, block 1 by the 8 pixel high ones
+const SMALL_FONT_OFFSET: usize = 0x4000;
+pub const PLANE_WINDOW: usize = 0x8000;
+
+// One byte per row, the leftmost pixel is the high bit
+pub type Font = [[u8; GLYPH_HEIGHT]; GLYPHS];
+
+type FontPlane = [Volatile<u8>; PLANE_WINDOW];
+
+// Both font blocks as they sit in plane 2
+pub type FontBlocks = [u8; PLANE_WINDOW];
+
+pub const fn is_glyph_height(height: usize) -> bool {
+    height == GLYPH_HEIGHT || height == SMALL_GLYPH_HEIGHT
+}
+
+// Plane 2 holds the fonts. It is mapped flat over the text buffer while f runs,
+// the text itself in planes 0 and 1 can't be reached in the meantime.
+pub fn with_font_plane<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut FontPlane) -> R,
+{
+    let map_mask = read_indexed(SEQUENCER_ADDRESS, sequencer::MAP_MASK);
+    let memory_mode = read_indexed(SEQUENCER_ADDRESS, sequencer::MEMORY_MODE);
+    let read_map = read_indexed(GRAPHICS_ADDRESS, graphics::READ_MAP);
+    let graphics_mode = read_indexed(GRAPHICS_ADDRESS, graphics::MODE);
+    let graphics_misc = read_indexed(GRAPHICS_ADDRESS, graphics::MISC);
+
+    write_indexed(SEQUENCER_ADDRESS, sequencer::MAP_MASK, 1 << FONT_PLANE);
+    write_indexed(
+        SEQUENCER_ADDRESS,
+        sequencer::MEMORY_MODE,
+        memory_mode | sequencer::SEQUENTIAL,
+    );
+    write_indexed(GRAPHICS_ADDRESS, graphics::READ_MAP, FONT_PLANE);
+    write_indexed(
+        GRAPHICS_ADDRESS,
+        graphics::MODE,
+        graphics_mode & !graphics::ODD_EVEN_READ,
+    );
+    write_indexed(
+        GRAPHICS_ADDRESS,
+        graphics::MISC,
+        graphics_misc & !graphics::CHAIN_ODD_EVEN,
+    );
+
+    let ret = f(unsafe { &mut *(VGA_ADDRESS as *mut FontPlane) });
+
+    write_indexed(SEQUENCER_ADDRESS, sequencer::MAP_MASK, map_mask);
+    write_indexed(SEQUENCER_ADDRESS, sequencer::MEMORY_MODE, memory_mode);
+    write_indexed(GRAPHICS_ADDRESS, graphics::READ_MAP, read_map);
+    write_indexed(GRAPHICS_ADDRESS, graphics::MODE, graphics_mode);
+    write_indexed(GRAPHICS_ADDRESS, graphics::MISC, graphics_misc);
+    ret
+}
+
+// The 16 pixel high font, as loaded by the BIOS before the first upload
+pub fn read_font(plane: &FontPlane) -> Font {
+    let mut font = [[0; GLYPH_HEIGHT]; GLYPHS];
+    for (glyph, rows) in font.iter_mut().enumerate() {
+        for (row, byte) in rows.iter_mut().enumerate() {
+            *byte = plane[glyph * GLYPH_STRIDE + row].read();
+        }
+    }
+    font
+}
+
+// Graphics modes draw over plane 2, the blocks are put back when leaving them
+pub fn save_blocks(plane: &FontPlane, blocks: &mut FontBlocks) {
+    for (byte, cell) in blocks.iter_mut().zip(plane) {
+        *byte = cell.read();
+    }
+}
+
+pub fn restore_blocks(plane: &mut FontPlane, blocks: &FontBlocks) {
+    for (cell, &byte) in plane.iter_mut().zip(blocks) {
+        cell.write(byte);
+    }
+}
+
+// 8 pixel high glyphs are doubled, the height must pass is_glyph_height
+pub fn scale_glyph(rows: &[u8]) -> [u8; GLYPH_HEIGHT] {
+    let small = rows.len() == SMALL_GLYPH_HEIGHT;
+    let mut glyph = [0; GLYPH_HEIGHT];
+    for (row, byte) in glyph.iter_mut().enumerate() {
+        *byte = if small { rows[row >> 1] } else { rows[row] };
+    }
+    glyph
+}
+
+// Fills both font blocks, 16 pixel high glyphs merge two rows at a time
+// for the small one so thin strokes survive
+pub fn write_glyph(plane: &mut FontPlane, code: u8, rows: &[u8]) {
+    let base = usize::from(code) * GLYPH_STRIDE;
+    let small = rows.len() == SMALL_GLYPH_HEIGHT;
+    for (row, large) in scale_glyph(rows).into_iter().enumerate() {
+        plane[base + row].write(large);
+    }
+    for row in 0..SMALL_GLYPH_HEIGHT {
+        let merged = if small {
+            rows[row]
+        } else {
+            rows[2 * row] | rows[2 * row + 1]
+        };
+        plane[SMALL_FONT_OFFSET + base + row].write(merged);
+    }
+}
+
+// Glyphs replacing the BIOS ones with the default font, by code
+pub fn default_glyphs() -> impl Iterator<Item = (u8, &'static [u8; SMALL_GLYPH_HEIGHT])> {
+    (b' '..)
+        .zip(&kfs::ASCII)
+        .chain((0xB0..).zip(&kfs::GRAPHICS))
+}
+
+// For the framebuffer, where there is no BIOS font to start from
+pub fn default_font() -> Font {
+    let mut font = [scale_glyph(&kfs::MISSING); GLYPHS];
+    for (code, rows) in default_glyphs() {
+        font[usize::from(code)] = scale_glyph(rows);
+    }
+    font
+}
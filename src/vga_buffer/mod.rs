@@ -0,0 +1,1108 @@
+mod ansi;
+pub mod canvas;
+mod console;
+pub mod font;
+pub mod mode;
+pub mod terminal;
+
+use {
+    crate::{clipboard::Clipboard, framebuffer::Framebuffer, interrupts, port::Port},
+    alloc::boxed::Box,
+    canvas::Canvas,
+    console::FramebufferConsole,
+    core::fmt::{self, Write as _},
+    font::{Font, FontBlocks, GLYPHS},
+    mode::TextMode,
+    spin::Mutex,
+    terminal::VirtualTerminal,
+    volatile::Volatile,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Color {
+    Black = 0,
+    Blue = 1,
+    Green = 2,
+    Cyan = 3,
+    Red = 4,
+    Magenta = 5,
+    Brown = 6,
+    LightGray = 7,
+    DarkGray = 8,
+    LightBlue = 9,
+    LightGreen = 10,
+    LightCyan = 11,
+    LightRed = 12,
+    Pink = 13,
+    Yellow = 14,
+    White = 15,
+}
+
+// Indexed by the SGR color codes (30-37, 90-97 for the bright ones)
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Brown,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::LightGray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::Yellow,
+    Color::LightBlue,
+    Color::Pink,
+    Color::LightCyan,
+    Color::White,
+];
+
+// The 16 colors above in the default VGA palette, as 0xRRGGBB
+const PALETTE: [u32; 16] = [
+    0x00_0000, 0x00_00AA, 0x00_AA00, 0x00_AAAA, 0xAA_0000, 0xAA_00AA, 0xAA_5500, 0xAA_AAAA,
+    0x55_5555, 0x55_55FF, 0x55_FF55, 0x55_FFFF, 0xFF_5555, 0xFF_55FF, 0xFF_FF55, 0xFF_FFFF,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+struct ColorCode(u8);
+
+impl ColorCode {
+    const fn new(foreground: Color, background: Color) -> Self {
+        Self((background as u8) << 4 | (foreground as u8))
+    }
+
+    // Bright backgrounds would blink, so the inverted foreground loses its intensity bit
+    const fn inverted(self) -> Self {
+        let foreground = self.0 & 0x07;
+        let background = (self.0 >> 4) & 0x07;
+        if foreground == background {
+            Self::new(Color::Black, Color::LightGray)
+        } else {
+            Self(foreground << 4 | background)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Attributes {
+    foreground: Color,
+    background: Color,
+    bold: bool,
+    blink: bool,
+    reverse: bool,
+}
+
+impl Attributes {
+    const DEFAULT: Self = Self {
+        foreground: Color::White,
+        background: Color::Black,
+        bold: false,
+        blink: false,
+        reverse: false,
+    };
+
+    // Bold is rendered as the bright variant of the foreground. The high bit of the
+    // background either blinks or brightens it, depending on Writer::set_blink.
+    const fn color_code(self) -> ColorCode {
+        let foreground = if self.bold {
+            self.foreground as u8 | 0x08
+        } else {
+            self.foreground as u8
+        };
+        let background = if self.blink {
+            self.background as u8 | 0x08
+        } else {
+            self.background as u8
+        };
+        if self.reverse {
+            ColorCode(foreground << 4 | background)
+        } else {
+            ColorCode(background << 4 | foreground)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct ScreenChar {
+    ascii_character: u8,
+    color_code: ColorCode,
+}
+
+impl ScreenChar {
+    const fn black_space() -> Self {
+        Self {
+            ascii_character: b' ',
+            color_code: ColorCode::new(Color::Black, Color::Black),
+        }
+    }
+
+    const fn white_space() -> Self {
+        Self {
+            ascii_character: b' ',
+            color_code: ColorCode::new(Color::White, Color::Black),
+        }
+    }
+
+    const fn inverted(self) -> Self {
+        Self {
+            ascii_character: self.ascii_character,
+            color_code: self.color_code.inverted(),
+        }
+    }
+}
+
+mod crtc {
+    pub const MAXIMUM_SCAN_LINE: u8 = 0x09;
+    pub const CURSOR_START: u8 = 0x0A;
+    pub const CURSOR_END: u8 = 0x0B;
+    pub const CURSOR_LOCATION_HIGH: u8 = 0x0E;
+    pub const CURSOR_LOCATION_LOW: u8 = 0x0F;
+    pub const VERTICAL_RETRACE_END: u8 = 0x11;
+
+    pub const CURSOR_DISABLE: u8 = 1 << 5; // in CURSOR_START
+    pub const SCAN_LINE_MASK: u8 = 0x1F;
+    pub const WRITE_PROTECT: u8 = 1 << 7; // in VERTICAL_RETRACE_END, covers registers 0 to 7
+}
+
+fn read_crtc(index: u8) -> u8 {
+    unsafe { Port::new(0x3D4).write(index) }
+    unsafe { Port::new(0x3D5).read() }
+}
+
+fn write_crtc(index: u8, value: u8) {
+    unsafe { Port::new(0x3D4).write(index) }
+    unsafe { Port::new(0x3D5).write(value) }
+}
+
+fn update_cursor(width: usize, row: usize, col: usize) {
+    let pos = row * width + col;
+    write_crtc(crtc::CURSOR_LOCATION_HIGH, (pos >> 8) as u8);
+    write_crtc(crtc::CURSOR_LOCATION_LOW, (pos & 0xFF) as u8);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorShape {
+    Underline,
+    Half,
+    Block,
+}
+
+// Relative to the height of the font, so the shapes survive a mode change
+fn set_cursor_shape(shape: CursorShape) {
+    let last = read_crtc(crtc::MAXIMUM_SCAN_LINE) & crtc::SCAN_LINE_MASK;
+    let start = match shape {
+        CursorShape::Underline => last.saturating_sub(1),
+        CursorShape::Half => (last + 1) >> 1,
+        CursorShape::Block => 0,
+    };
+    let start_register = read_crtc(crtc::CURSOR_START) & !crtc::SCAN_LINE_MASK;
+    let end_register = read_crtc(crtc::CURSOR_END) & !crtc::SCAN_LINE_MASK;
+    write_crtc(crtc::CURSOR_START, start_register | start);
+    write_crtc(crtc::CURSOR_END, end_register | last);
+}
+
+fn set_cursor_enabled(enabled: bool) {
+    let start_register = read_crtc(crtc::CURSOR_START);
+    write_crtc(
+        crtc::CURSOR_START,
+        if enabled {
+            start_register & !crtc::CURSOR_DISABLE
+        } else {
+            start_register | crtc::CURSOR_DISABLE
+        },
+    );
+}
+
+fn set_blink_enabled(enabled: bool) {
+    const INPUT_STATUS_1: u16 = 0x3DA;
+    const ATTRIBUTE_ADDRESS: u16 = 0x3C0;
+    const ATTRIBUTE_DATA_READ: u16 = 0x3C1;
+    const MODE_CONTROL: u8 = 0x10;
+    const PALETTE_ADDRESS_SOURCE: u8 = 1 << 5; // the screen goes blank without it
+    const BLINK_ENABLE: u8 = 1 << 3;
+
+    let mut address_register: Port<u8> = Port::new(ATTRIBUTE_ADDRESS);
+    // Reading the input status resets the address/data flip-flop
+    let _: u8 = unsafe { Port::new(INPUT_STATUS_1).read() };
+    unsafe { address_register.write(MODE_CONTROL | PALETTE_ADDRESS_SOURCE) }
+    let mode: u8 = unsafe { Port::new(ATTRIBUTE_DATA_READ).read() };
+    unsafe {
+        address_register.write(if enabled {
+            mode | BLINK_ENABLE
+        } else {
+            mode & !BLINK_ENABLE
+        });
+    }
+}
+
+const VGA_ADDRESS: usize = 0xb8000;
+// Largest screen in cells, the actual size depends on the text mode or the framebuffer
+pub const VGA_MAX_WIDTH: usize = 160;
+pub const VGA_MAX_HEIGHT: usize = 64;
+pub const VGA_HISTORY: usize = 200;
+const _: () = assert!(
+    VGA_HISTORY >= VGA_MAX_HEIGHT,
+    "history must hold a full screen"
+);
+// Alt+F1 to Alt+F12, the last one is the kernel log
+pub const MAX_TERMINALS: usize = 12;
+pub const LOG_TERMINAL: usize = MAX_TERMINALS - 1;
+// Special screens on F11 and F12, they are always open
+pub const WARNING_TERMINAL: usize = MAX_TERMINALS;
+pub const DEBUG_TERMINAL: usize = MAX_TERMINALS + 1;
+pub const TERMINAL_SLOTS: usize = MAX_TERMINALS + 2;
+const ATTRIBUTE_STACK_SIZE: usize = 16;
+// Set up by the BIOS before the kernel starts
+pub const BOOT_MODE: TextMode = TextMode::Text80x25;
+
+const NO_LINE: usize = usize::MAX;
+
+// Rows are packed with the width of the current mode
+#[repr(transparent)]
+struct Buffer {
+    chars: [Volatile<ScreenChar>; VGA_MAX_WIDTH * VGA_MAX_HEIGHT],
+}
+
+// Only the first columns are shown in the narrower modes
+type Line = [ScreenChar; VGA_MAX_WIDTH];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct HistoryPos {
+    line: usize,
+    col: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Selection {
+    anchor: HistoryPos,
+    head: HistoryPos,
+}
+
+impl Selection {
+    fn bounds(&self) -> (HistoryPos, HistoryPos) {
+        (self.anchor.min(self.head), self.anchor.max(self.head))
+    }
+
+    fn contains(&self, pos: HistoryPos) -> bool {
+        let (start, end) = self.bounds();
+        start <= pos && pos <= end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusBar {
+    Hidden,
+    Top,
+    Bottom,
+}
+
+const STATUS_COLOR: ColorCode = ColorCode::new(Color::Black, Color::LightGray);
+
+// What replaced the VGA text mode at runtime, until the writer goes back to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Takeover {
+    Graphics,
+    Framebuffer,
+}
+
+// Only called by the writer, behind the WRITER lock
+fn vga_buffer() -> &'static mut Buffer {
+    unsafe { &mut *(VGA_ADDRESS as *mut Buffer) }
+}
+
+// Compositor: the only one touching the VGA buffer, it shows the foreground
+// terminal with the mouse pointer and the selection drawn on top of it.
+pub struct Writer {
+    active: usize,
+    // On the heap, a terminal only takes memory while it is open
+    terminals: [Option<Box<VirtualTerminal>>; TERMINAL_SLOTS],
+    pointer: Option<(usize, usize)>,
+    selection: Option<Selection>,
+    // Replaces the VGA text buffer when the bootloader set up a framebuffer
+    console: Option<FramebufferConsole>,
+    mode: TextMode,
+    blink: bool,
+    // Kept aside before the first upload, plane 2 only holds the current font afterwards
+    bios_font: Option<Font>,
+    takeover: Option<Takeover>,
+    // The DAC and plane 2 of the text mode during a takeover
+    text_palette: [[u8; 3]; GLYPHS],
+    text_fonts: FontBlocks,
+    // Physical line of the active terminal shown on each row of the VGA buffer
+    drawn: [usize; VGA_MAX_HEIGHT],
+    status_bar: StatusBar,
+    status: Line,
+    status_dirty: bool,
+    // What the CRTC currently shows, to only reprogram it on changes
+    cursor_shown: Option<CursorShape>,
+    // Cell holding the cursor, which is drawn in software on the framebuffer
+    cursor_cell: Option<(usize, usize)>,
+}
+
+impl Writer {
+    // The active terminal can't be closed, so it is always open
+    fn active_terminal(&mut self) -> &mut VirtualTerminal {
+        self.terminals[self.active].as_deref_mut().unwrap()
+    }
+
+    fn displayed(&self) -> &VirtualTerminal {
+        self.terminals[self.active].as_deref().unwrap()
+    }
+
+    pub const fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn is_open(&self, idx: usize) -> bool {
+        self.terminals.get(idx).is_some_and(Option::is_some)
+    }
+
+    fn new_terminal(&self) -> Box<VirtualTerminal> {
+        Box::new(VirtualTerminal::new(self.width(), self.text_rows()))
+    }
+
+    // The first shell, the kernel log and the special screens, before anything is written
+    pub fn open_boot_terminals(&mut self) {
+        for idx in [0, LOG_TERMINAL, WARNING_TERMINAL, DEBUG_TERMINAL] {
+            self.terminals[idx] = Some(self.new_terminal());
+        }
+    }
+
+    pub fn open_terminal(&mut self, idx: usize) -> bool {
+        if idx >= MAX_TERMINALS || self.is_open(idx) {
+            return false;
+        }
+        self.terminals[idx] = Some(self.new_terminal());
+        true
+    }
+
+    // Its memory goes back to the heap
+    pub fn close_terminal(&mut self, idx: usize) -> bool {
+        if idx == self.active || idx >= LOG_TERMINAL || !self.is_open(idx) {
+            return false;
+        }
+        self.terminals[idx] = None;
+        true
+    }
+
+    // Background terminals are written without touching the hardware
+    pub fn with_terminal<F, R>(&mut self, idx: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut VirtualTerminal) -> R,
+    {
+        let ret = f(self.terminals.get_mut(idx)?.as_deref_mut()?);
+        if idx == self.active {
+            self.present();
+        }
+        Some(ret)
+    }
+
+    pub fn write_byte(&mut self, byte: u8) {
+        self.active_terminal().write_byte(byte);
+        self.present();
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.active_terminal().write_bytes(bytes);
+        self.present();
+    }
+
+    pub fn write_glyphs(&mut self, glyphs: &[u8]) {
+        self.active_terminal().write_glyphs(glyphs);
+        self.present();
+    }
+
+    // Without blinking, the 8 bright colors become available as backgrounds
+    pub fn set_blink(&mut self, enabled: bool) {
+        self.blink = enabled;
+        if self.owns_text_mode() {
+            set_blink_enabled(enabled);
+        }
+        self.invalidate();
+        self.present();
+    }
+
+    pub fn set_cursor(&mut self, col: usize) {
+        self.active_terminal().set_cursor(col);
+        self.present();
+    }
+
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.active_terminal().set_cursor_shape(shape);
+        self.present();
+    }
+
+    pub fn switch_screen(&mut self, screen_idx: usize) -> bool {
+        if screen_idx == self.active || !self.is_open(screen_idx) {
+            return false;
+        }
+        self.active = screen_idx;
+        self.selection = None;
+        self.invalidate();
+        self.present();
+        true
+    }
+
+    pub fn clear_vga_buffer(&mut self) {
+        match self.console.as_mut() {
+            Some(console) => console.clear(),
+            None => {
+                for sc in &mut vga_buffer().chars {
+                    sc.write(ScreenChar::black_space());
+                }
+            }
+        }
+        self.invalidate();
+    }
+
+    // The VGA text buffer is left for good, unless switch_to_framebuffer kept its state
+    pub fn use_framebuffer(&mut self, framebuffer: Framebuffer) {
+        self.console = Some(FramebufferConsole::new(framebuffer));
+        self.resize();
+    }
+
+    // acquire sets up a framebuffer in the VGA memory, which it may clear,
+    // so the text mode state is kept aside first for switch_to_text
+    pub fn switch_to_framebuffer<F>(&mut self, acquire: F) -> bool
+    where
+        F: FnOnce() -> Option<Framebuffer>,
+    {
+        if self.in_graphics() {
+            return false;
+        }
+        let from_text = self.console.is_none();
+        if from_text {
+            self.save_text_state();
+        }
+        let Some(framebuffer) = acquire() else {
+            return false;
+        };
+        if from_text {
+            self.takeover = Some(Takeover::Framebuffer);
+        }
+        self.use_framebuffer(framebuffer);
+        true
+    }
+
+    // release hands the VGA registers back, only possible after switch_to_framebuffer
+    pub fn switch_to_text<F>(&mut self, release: F) -> bool
+    where
+        F: FnOnce(),
+    {
+        if self.takeover != Some(Takeover::Framebuffer) {
+            return false;
+        }
+        release();
+        self.takeover = None;
+        self.console = None;
+        self.cursor_cell = None;
+        self.restore_text_state();
+        self.resize();
+        true
+    }
+
+    pub fn framebuffer(&self) -> Option<&Framebuffer> {
+        self.console.as_ref().map(FramebufferConsole::framebuffer)
+    }
+
+    // None on the framebuffer
+    pub const fn mode(&self) -> Option<TextMode> {
+        match self.console {
+            Some(_) => None,
+            None => Some(self.mode),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.console
+            .as_ref()
+            .map_or_else(|| self.mode.width(), FramebufferConsole::cols)
+    }
+
+    pub fn height(&self) -> usize {
+        self.console
+            .as_ref()
+            .map_or_else(|| self.mode.height(), FramebufferConsole::rows)
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+
+    // Neither on the framebuffer nor in graphics mode
+    const fn owns_text_mode(&self) -> bool {
+        self.console.is_none() && self.takeover.is_none()
+    }
+
+    // Only in VGA text mode
+    pub fn set_mode(&mut self, mode: TextMode) -> bool {
+        if !self.owns_text_mode() {
+            return false;
+        }
+        mode::set_text_mode(mode);
+        // The attribute controller was reprogrammed with blinking on
+        set_blink_enabled(self.blink);
+        self.mode = mode;
+        self.resize();
+        true
+    }
+
+    // The terminals keep their bottom line, the pointer comes back inside
+    // the screen with the next mouse move if it fell outside
+    fn resize(&mut self) {
+        let (width, height) = self.size();
+        if self
+            .pointer
+            .is_some_and(|(row, col)| row >= height || col >= width)
+        {
+            self.pointer = None;
+        }
+        self.selection = None;
+        self.resize_terminals();
+        self.clear_vga_buffer();
+        self.present();
+    }
+
+    fn save_bios_font(&mut self) -> &Font {
+        self.bios_font
+            .get_or_insert_with(|| font::with_font_plane(|plane| font::read_font(plane)))
+    }
+
+    // Fonts in plane 2 survive mode changes and nothing has to be redrawn,
+    // the framebuffer has to draw the cells again
+    fn upload_glyphs<'rows, I>(&mut self, glyphs: I)
+    where
+        I: IntoIterator<Item = (u8, &'rows [u8])>,
+    {
+        if let Some(console) = self.console.as_mut() {
+            for (code, rows) in glyphs {
+                console.set_glyph(code, rows);
+            }
+            self.invalidate();
+            self.present();
+        } else {
+            self.save_bios_font();
+            font::with_font_plane(|plane| {
+                for (code, rows) in glyphs {
+                    font::write_glyph(plane, code, rows);
+                }
+            });
+        }
+    }
+
+    // 256 glyphs of 8 or 16 rows each, the layout of PSF fonts and BIOS dumps
+    pub fn load_font(&mut self, data: &[u8]) -> bool {
+        let height = data.len().div_euclid(GLYPHS);
+        if self.in_graphics() || data.len() != height * GLYPHS || !font::is_glyph_height(height) {
+            return false;
+        }
+        self.upload_glyphs((0..=u8::MAX).zip(data.chunks_exact(height)));
+        true
+    }
+
+    pub fn set_glyph(&mut self, code: u8, rows: &[u8]) -> bool {
+        if self.in_graphics() || !font::is_glyph_height(rows.len()) {
+            return false;
+        }
+        self.upload_glyphs([(code, rows)]);
+        true
+    }
+
+    // There is no BIOS font to go back to on the framebuffer
+    pub fn restore_bios_font(&mut self) -> bool {
+        if !self.owns_text_mode() {
+            return false;
+        }
+        let bios_font = *self.save_bios_font();
+        self.load_font(bios_font.as_flattened())
+    }
+
+    // The BIOS font with the kfs ASCII and box drawing characters
+    pub fn load_default_font(&mut self) {
+        if self.in_graphics() {
+            return;
+        }
+        if let Some(console) = self.console.as_mut() {
+            console.reset_font();
+            self.invalidate();
+            self.present();
+            return;
+        }
+        self.restore_bios_font();
+        self.upload_glyphs(font::default_glyphs().map(|(code, rows)| (code, rows.as_slice())));
+    }
+
+    // Switches to mode 13h, the terminals keep running out of sight.
+    // The canvas borrows the writer, so leave_graphics can't be called while it exists.
+    pub fn enter_graphics(&mut self) -> Option<Canvas<'_>> {
+        if !self.owns_text_mode() {
+            return None;
+        }
+        self.save_text_state();
+        mode::set_graphics_mode();
+        self.takeover = Some(Takeover::Graphics);
+        // The mode was just set, and the borrow of the writer keeps it
+        let mut canvas = unsafe { Canvas::new() };
+        canvas::load_default_palette();
+        canvas.clear(0);
+        Some(canvas)
+    }
+
+    pub fn in_graphics(&self) -> bool {
+        self.takeover == Some(Takeover::Graphics)
+    }
+
+    // Back to the text mode left by enter_graphics, with its colors and fonts
+    pub fn leave_graphics(&mut self) {
+        if !self.in_graphics() {
+            return;
+        }
+        self.restore_text_state();
+        self.takeover = None;
+        self.clear_vga_buffer();
+        self.present();
+    }
+
+    fn save_text_state(&mut self) {
+        font::with_font_plane(|plane| font::save_blocks(plane, &mut self.text_fonts));
+        for (index, rgb) in (0..=u8::MAX).zip(&mut self.text_palette) {
+            *rgb = mode::read_dac(index);
+        }
+    }
+
+    // The text is drawn again by the caller
+    fn restore_text_state(&self) {
+        mode::set_text_mode(self.mode);
+        set_blink_enabled(self.blink);
+        for (index, &rgb) in (0..=u8::MAX).zip(&self.text_palette) {
+            mode::write_dac(index, rgb);
+        }
+        font::with_font_plane(|plane| font::restore_blocks(plane, &self.text_fonts));
+    }
+
+    fn resize_terminals(&mut self) {
+        let (cols, rows) = (self.width(), self.text_rows());
+        for terminal in self.terminals.iter_mut().flatten() {
+            terminal.set_size(cols, rows);
+        }
+    }
+
+    pub fn clear_screen(&mut self) {
+        self.active_terminal().clear();
+        self.present();
+    }
+
+    pub fn move_up(&mut self) {
+        self.active_terminal().move_up();
+        self.present();
+    }
+
+    pub fn move_down(&mut self) {
+        self.active_terminal().move_down();
+        self.present();
+    }
+
+    pub fn move_all_the_way_up(&mut self) {
+        self.active_terminal().move_all_the_way_up();
+        self.present();
+    }
+
+    pub fn move_all_the_way_down(&mut self) {
+        self.active_terminal().move_all_the_way_down();
+        self.present();
+    }
+
+    pub const fn status_bar(&self) -> StatusBar {
+        self.status_bar
+    }
+
+    // The terminals lose or regain a row, their bottom line stays in place
+    pub fn set_status_bar(&mut self, status_bar: StatusBar) {
+        self.status_bar = status_bar;
+        self.resize_terminals();
+        self.invalidate();
+        self.present();
+    }
+
+    // Truncated to the width of the screen, nothing is drawn while hidden
+    pub fn set_status(&mut self, text: &[u8]) {
+        let mut status = [ScreenChar {
+            ascii_character: b' ',
+            color_code: STATUS_COLOR,
+        }; VGA_MAX_WIDTH];
+        for (sc, &byte) in status[..self.width()].iter_mut().zip(text) {
+            sc.ascii_character = byte;
+        }
+        if status != self.status {
+            self.status = status;
+            self.status_dirty = true;
+            self.present();
+        }
+    }
+
+    fn status_row(&self) -> Option<usize> {
+        match self.status_bar {
+            StatusBar::Hidden => None,
+            StatusBar::Top => Some(0),
+            StatusBar::Bottom => Some(self.height() - 1),
+        }
+    }
+
+    fn text_rows(&self) -> usize {
+        match self.status_bar {
+            StatusBar::Hidden => self.height(),
+            StatusBar::Top | StatusBar::Bottom => self.height() - 1,
+        }
+    }
+
+    const fn first_text_row(&self) -> usize {
+        match self.status_bar {
+            StatusBar::Top => 1,
+            StatusBar::Hidden | StatusBar::Bottom => 0,
+        }
+    }
+
+    pub fn set_pointer(&mut self, row: usize, col: usize) {
+        if row < self.height() && col < self.width() {
+            if let Some((old_row, old_col)) = self.pointer.replace((row, col)) {
+                self.draw_cell(old_row, old_col);
+            }
+            self.draw_cell(row, col);
+        }
+    }
+
+    pub fn start_selection(&mut self, row: usize, col: usize) {
+        let pos = self.history_pos(row, col);
+        self.selection = Some(Selection {
+            anchor: pos,
+            head: pos,
+        });
+        self.invalidate();
+        self.present();
+    }
+
+    pub fn extend_selection(&mut self, row: usize, col: usize) {
+        let pos = self.history_pos(row, col);
+        if let Some(selection) = self.selection.as_mut() {
+            selection.head = pos;
+            self.invalidate();
+            self.present();
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        if self.selection.take().is_some() {
+            self.invalidate();
+            self.present();
+        }
+    }
+
+    // A simple click selects nothing, it only clears the previous selection
+    pub fn copy_selection(&mut self, clipboard: &mut Clipboard) {
+        let Some(selection) = self.selection else {
+            return;
+        };
+        if selection.anchor == selection.head {
+            self.clear_selection();
+            return;
+        }
+        clipboard.clear();
+        let (start, end) = selection.bounds();
+        let terminal = self.displayed();
+        for line in start.line..=end.line {
+            let from = if line == start.line { start.col } else { 0 };
+            let to = if line == end.line {
+                end.col + 1
+            } else {
+                self.width()
+            };
+            let chars = &terminal.line(line)[from..to];
+            let len = chars
+                .iter()
+                .rposition(|sc| sc.ascii_character != b' ')
+                .map_or(0, |last| last + 1);
+            for sc in &chars[..len] {
+                clipboard.push(sc.ascii_character);
+            }
+            if line != end.line {
+                clipboard.push(b'\n');
+            }
+        }
+    }
+
+    // Rows of the status bar select the closest line of text
+    fn history_pos(&self, row: usize, col: usize) -> HistoryPos {
+        let text_row = row.saturating_sub(self.first_text_row());
+        let last_row = self.text_rows() - 1;
+        HistoryPos {
+            line: self.displayed().visible_line(if text_row < last_row {
+                text_row
+            } else {
+                last_row
+            }),
+            col,
+        }
+    }
+
+    fn visible_char(&self, row: usize, col: usize) -> ScreenChar {
+        let is_pointer = self.pointer == Some((row, col));
+        if self.status_row() == Some(row) {
+            let sc = self.status[col];
+            return if is_pointer { sc.inverted() } else { sc };
+        }
+        let pos = self.history_pos(row, col);
+        let sc = self.displayed().line(pos.line)[col];
+        let is_selected = self
+            .selection
+            .is_some_and(|selection| selection.contains(pos));
+        if is_pointer ^ is_selected {
+            sc.inverted()
+        } else {
+            sc
+        }
+    }
+
+    fn draw_cell(&mut self, row: usize, col: usize) {
+        let sc = self.visible_char(row, col);
+        let width = self.width();
+        let cursor = self
+            .cursor_shown
+            .filter(|_| self.cursor_cell == Some((row, col)));
+        match self.console.as_mut() {
+            Some(console) => console.draw_cell(row, col, sc, cursor),
+            None => vga_buffer().chars[row * width + col].write(sc),
+        }
+    }
+
+    const fn invalidate(&mut self) {
+        self.drawn = [NO_LINE; VGA_MAX_HEIGHT];
+        self.status_dirty = true;
+        self.cursor_shown = None;
+    }
+
+    // Only rewrites the rows showing another line than last time or a modified one
+    fn present(&mut self) {
+        if self.in_graphics() {
+            return;
+        }
+        if self.active_terminal().take_scrolled() {
+            // The selected lines just moved
+            self.selection = None;
+        }
+        let offset = self.first_text_row();
+        for y in 0..self.text_rows() {
+            let terminal = self.active_terminal();
+            let idx = terminal.physical(terminal.visible_line(y));
+            let dirty = terminal.take_dirty(idx);
+            if dirty || self.drawn[y + offset] != idx {
+                for x in 0..self.width() {
+                    self.draw_cell(y + offset, x);
+                }
+                self.drawn[y + offset] = idx;
+            }
+        }
+        if let Some(row) = self.status_row()
+            && self.status_dirty
+        {
+            for x in 0..self.width() {
+                self.draw_cell(row, x);
+            }
+        }
+        self.status_dirty = false;
+        let shape = self.displayed().cursor_shape();
+        let cursor = self
+            .displayed()
+            .cursor()
+            .map(|(row, col)| (row + offset, col));
+        if self.console.is_some() {
+            // The cell losing the cursor is drawn again without it
+            let shown = cursor.map(|_| shape);
+            if self.cursor_cell != cursor || self.cursor_shown != shown {
+                let previous = self.cursor_cell;
+                self.cursor_cell = cursor;
+                self.cursor_shown = shown;
+                for (row, col) in previous.into_iter().chain(cursor) {
+                    self.draw_cell(row, col);
+                }
+            }
+            return;
+        }
+        match cursor {
+            Some((row, col)) => {
+                update_cursor(self.width(), row, col);
+                if self.cursor_shown.replace(shape) != Some(shape) {
+                    set_cursor_shape(shape);
+                    set_cursor_enabled(true);
+                }
+            }
+            None => {
+                if self.cursor_shown.take().is_some() {
+                    set_cursor_enabled(false);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Write for Writer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_fmt(format_args!("{s}"))
+    }
+
+    // Formats straight into the terminal, so print! redraws and moves the cursor once
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        let result = fmt::write(self.active_terminal(), args);
+        self.present();
+        result
+    }
+}
+
+// The terminals come from open_boot_terminals once the heap can be used
+pub static WRITER: Mutex<Writer> = Mutex::new(Writer {
+    active: 0,
+    terminals: [const { None }; TERMINAL_SLOTS],
+    pointer: None,
+    selection: None,
+    console: None,
+    mode: BOOT_MODE,
+    blink: true,
+    bios_font: None,
+    takeover: None,
+    text_palette: [[0; 3]; GLYPHS],
+    text_fonts: [0; font::PLANE_WINDOW],
+    drawn: [NO_LINE; VGA_MAX_HEIGHT],
+    status_bar: StatusBar::Hidden,
+    status: [ScreenChar::black_space(); VGA_MAX_WIDTH],
+    status_dirty: false,
+    cursor_shown: None,
+    cursor_cell: None,
+});
+
+// Restores the previous colors afterwards, even if f changed them. They are
+// restored on the terminal they were set on, even if f switched to another one.
+pub fn with_color<F, R>(foreground: Color, background: Color, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let idx = interrupts::without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let idx = writer.active();
+        writer.with_terminal(idx, |terminal| {
+            terminal.push_attributes();
+            terminal.set_foreground_color(foreground);
+            terminal.set_background_color(background);
+        });
+        idx
+    });
+    let ret = f();
+    interrupts::without_interrupts(|| {
+        WRITER
+            .lock()
+            .with_terminal(idx, VirtualTerminal::pop_attributes)
+    });
+    ret
+}
+
+// Whether print! has somewhere to go, the terminals are only opened by kernel_main
+pub fn has_terminal() -> bool {
+    interrupts::without_interrupts(|| {
+        let writer = WRITER.lock();
+        writer.is_open(writer.active())
+    })
+}
+
+// Straight into the VGA buffer left by the BIOS, from its top left corner.
+// For a panic before the terminals exist, what doesn't fit is dropped.
+struct RawWriter {
+    pos: usize,
+    color_code: ColorCode,
+}
+
+impl fmt::Write for RawWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let (cols, rows) = (BOOT_MODE.width(), BOOT_MODE.height());
+        for byte in s.bytes() {
+            if byte == b'\n' {
+                self.pos = self.pos.div_euclid(cols).saturating_add(1) * cols;
+                continue;
+            }
+            if self.pos >= cols * rows {
+                break;
+            }
+            vga_buffer().chars[self.pos].write(ScreenChar {
+                ascii_character: byte,
+                color_code: self.color_code,
+            });
+            self.pos += 1;
+        }
+        Ok(())
+    }
+}
+
+pub fn write_raw(foreground: Color, background: Color, args: fmt::Arguments<'_>) {
+    let mut writer = RawWriter {
+        pos: 0,
+        color_code: ColorCode::new(foreground, background),
+    };
+    writer.write_fmt(args).unwrap();
+}
+
+fn append_message(idx: usize, foreground: Color, args: fmt::Arguments<'_>) {
+    let now = interrupts::ticks(); // milliseconds
+    interrupts::without_interrupts(|| {
+        WRITER.lock().with_terminal(idx, |terminal| {
+            terminal.with_foreground_color(foreground, |writer| {
+                writeln!(
+                    writer,
+                    "[{:5}.{:03}] {}",
+                    now.div_euclid(1000),
+                    now.rem_euclid(1000),
+                    args
+                )
+                .unwrap();
+            });
+        });
+    });
+}
+
+// Kernel messages go to their own console, whichever one is displayed
+pub fn log(args: fmt::Arguments<'_>) {
+    append_message(LOG_TERMINAL, Attributes::DEFAULT.foreground, args);
+}
+
+pub fn warn(args: fmt::Arguments<'_>) {
+    append_message(WARNING_TERMINAL, Color::Yellow, args);
+}
+
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => ($crate::vga_buffer::log(format_args!($($arg)*)));
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => ($crate::vga_buffer::warn(format_args!($($arg)*)));
+}
+
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {{
+        use core::fmt::Write as _;
+        $crate::interrupts::without_interrupts(|| {
+            $crate::vga_buffer::WRITER
+                .lock()
+                .write_fmt(format_args!($($arg)*))
+                .unwrap();
+        });
+    }};
+}
+
+#[macro_export]
+macro_rules! println {
+    () => ($crate::print!("\n"));
+    ($($arg:tt)*) => ($crate::print!("{}\n", format_args!($($arg)*)));
+}
@@ -0,0 +1,235 @@
+// Register values from the public domain modes.c by Chris Giese
+use {
+    super::{crtc, read_crtc, write_crtc},
+    crate::port::Port,
+};
+
+const MISC_OUTPUT_WRITE: u16 = 0x3C2;
+pub const SEQUENCER_ADDRESS: u16 = 0x3C4;
+pub const GRAPHICS_ADDRESS: u16 = 0x3CE;
+const ATTRIBUTE_ADDRESS: u16 = 0x3C0; // also the data port on writes
+const INPUT_STATUS_1: u16 = 0x3DA;
+const DAC_READ_ADDRESS: u16 = 0x3C7;
+const DAC_WRITE_ADDRESS: u16 = 0x3C8;
+const DAC_DATA: u16 = 0x3C9; // red, green and blue follow each other
+
+pub mod sequencer {
+    pub const MAP_MASK: u8 = 0x02;
+    pub const MEMORY_MODE: u8 = 0x04;
+
+    pub const SEQUENTIAL: u8 = 1 << 2; // in MEMORY_MODE
+}
+
+pub mod graphics {
+    pub const READ_MAP: u8 = 0x04;
+    pub const MODE: u8 = 0x05;
+    pub const MISC: u8 = 0x06;
+
+    pub const ODD_EVEN_READ: u8 = 1 << 4; // in MODE
+    pub const CHAIN_ODD_EVEN: u8 = 1 << 1; // in MISC
+}
+
+const PALETTE_ADDRESS_SOURCE: u8 = 1 << 5; // the screen goes blank without it
+
+struct Registers {
+    misc: u8,
+    sequencer: [u8; 5],
+    crtc: [u8; 25],
+    graphics: [u8; 9],
+    attribute: [u8; 21],
+}
+
+const GRAPHICS: [u8; 9] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x0E, 0x00, 0xFF];
+const ATTRIBUTE: [u8; 21] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x14, 0x07, 0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E, 0x3F,
+    0x0C, 0x00, 0x0F, 0x08, 0x00,
+];
+
+const TEXT_80X25: Registers = Registers {
+    misc: 0x67,
+    sequencer: [0x03, 0x00, 0x03, 0x00, 0x02],
+    crtc: [
+        0x5F, 0x4F, 0x50, 0x82, 0x55, 0x81, 0xBF, 0x1F, 0x00, 0x4F, 0x0D, 0x0E, 0x00, 0x00, 0x00,
+        0x50, 0x9C, 0x0E, 0x8F, 0x28, 0x1F, 0x96, 0xB9, 0xA3, 0xFF,
+    ],
+    graphics: GRAPHICS,
+    attribute: ATTRIBUTE,
+};
+
+// The 8 pixel high modes select font block 1 in the character map register (sequencer 3)
+const TEXT_80X50: Registers = Registers {
+    misc: 0x67,
+    sequencer: [0x03, 0x00, 0x03, 0x05, 0x02],
+    crtc: [
+        0x5F, 0x4F, 0x50, 0x82, 0x55, 0x81, 0xBF, 0x1F, 0x00, 0x47, 0x06, 0x07, 0x00, 0x00, 0x01,
+        0x40, 0x9C, 0x8E, 0x8F, 0x28, 0x1F, 0x96, 0xB9, 0xA3, 0xFF,
+    ],
+    graphics: GRAPHICS,
+    attribute: ATTRIBUTE,
+};
+
+const TEXT_90X30: Registers = Registers {
+    misc: 0xE7,
+    sequencer: [0x03, 0x01, 0x03, 0x00, 0x02],
+    crtc: [
+        0x6B, 0x59, 0x5A, 0x82, 0x60, 0x8D, 0x0B, 0x3E, 0x00, 0x4F, 0x0D, 0x0E, 0x00, 0x00, 0x00,
+        0x00, 0xEA, 0x0C, 0xDF, 0x2D, 0x10, 0xE8, 0x05, 0xA3, 0xFF,
+    ],
+    graphics: GRAPHICS,
+    attribute: ATTRIBUTE,
+};
+
+const TEXT_90X60: Registers = Registers {
+    misc: 0xE7,
+    sequencer: [0x03, 0x01, 0x03, 0x05, 0x02],
+    crtc: [
+        0x6B, 0x59, 0x5A, 0x82, 0x60, 0x8D, 0x0B, 0x3E, 0x00, 0x47, 0x06, 0x07, 0x00, 0x00, 0x00,
+        0x00, 0xEA, 0x0C, 0xDF, 0x2D, 0x08, 0xE8, 0x05, 0xA3, 0xFF,
+    ],
+    graphics: GRAPHICS,
+    attribute: ATTRIBUTE,
+};
+
+// 320x200 with 256 colors, chain 4 puts one byte per pixel at 0xA0000
+const GRAPHICS_320X200: Registers = Registers {
+    misc: 0x63,
+    sequencer: [0x03, 0x01, 0x0F, 0x00, 0x0E],
+    crtc: [
+        0x5F, 0x4F, 0x50, 0x82, 0x54, 0x80, 0xBF, 0x1F, 0x00, 0x41, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x9C, 0x0E, 0x8F, 0x28, 0x40, 0x96, 0xB9, 0xA3, 0xFF,
+    ],
+    graphics: [0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x05, 0x0F, 0xFF],
+    attribute: [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+        0x0F, 0x41, 0x00, 0x0F, 0x00, 0x00,
+    ],
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextMode {
+    Text80x25,
+    Text80x50,
+    Text90x30,
+    Text90x60,
+}
+
+impl TextMode {
+    pub const ALL: [Self; 4] = [
+        Self::Text80x25,
+        Self::Text80x50,
+        Self::Text90x30,
+        Self::Text90x60,
+    ];
+
+    pub const fn width(self) -> usize {
+        match self {
+            Self::Text80x25 | Self::Text80x50 => 80,
+            Self::Text90x30 | Self::Text90x60 => 90,
+        }
+    }
+
+    pub const fn height(self) -> usize {
+        match self {
+            Self::Text80x25 => 25,
+            Self::Text90x30 => 30,
+            Self::Text80x50 => 50,
+            Self::Text90x60 => 60,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Text80x25 => "80x25",
+            Self::Text80x50 => "80x50",
+            Self::Text90x30 => "90x30",
+            Self::Text90x60 => "90x60",
+        }
+    }
+
+    pub fn from_name(name: &[u8]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().as_bytes() == name)
+    }
+
+    const fn registers(self) -> &'static Registers {
+        match self {
+            Self::Text80x25 => &TEXT_80X25,
+            Self::Text80x50 => &TEXT_80X50,
+            Self::Text90x30 => &TEXT_90X30,
+            Self::Text90x60 => &TEXT_90X60,
+        }
+    }
+}
+
+// The data port follows the address port
+pub fn write_indexed(address: u16, index: u8, value: u8) {
+    unsafe { Port::new(address).write(index) }
+    unsafe { Port::new(address + 1).write(value) }
+}
+
+pub fn read_indexed(address: u16, index: u8) -> u8 {
+    unsafe { Port::new(address).write(index) }
+    unsafe { Port::new(address + 1).read() }
+}
+
+fn program(registers: &Registers) {
+    unsafe { Port::new(MISC_OUTPUT_WRITE).write(registers.misc) }
+    for (index, &value) in (0..).zip(&registers.sequencer) {
+        write_indexed(SEQUENCER_ADDRESS, index, value);
+    }
+    write_crtc(
+        crtc::VERTICAL_RETRACE_END,
+        read_crtc(crtc::VERTICAL_RETRACE_END) & !crtc::WRITE_PROTECT,
+    );
+    for (index, &value) in (0..).zip(&registers.crtc) {
+        // Keeps the horizontal timings writable until the end of the loop
+        let unprotected = if index == crtc::VERTICAL_RETRACE_END {
+            value & !crtc::WRITE_PROTECT
+        } else {
+            value
+        };
+        write_crtc(index, unprotected);
+    }
+    for (index, &value) in (0..).zip(&registers.graphics) {
+        write_indexed(GRAPHICS_ADDRESS, index, value);
+    }
+    let mut attribute_register: Port<u8> = Port::new(ATTRIBUTE_ADDRESS);
+    for (index, &value) in (0..).zip(&registers.attribute) {
+        // Reading the input status resets the address/data flip-flop
+        let _: u8 = unsafe { Port::new(INPUT_STATUS_1).read() };
+        unsafe { attribute_register.write(index) }
+        unsafe { attribute_register.write(value) }
+    }
+    let _: u8 = unsafe { Port::new(INPUT_STATUS_1).read() };
+    unsafe { attribute_register.write(PALETTE_ADDRESS_SOURCE) }
+}
+
+// The caller redraws everything, the layout of the text buffer changes with the width
+pub fn set_text_mode(mode: TextMode) {
+    program(mode.registers());
+}
+
+// Mode 13h, drawing in it overwrites the fonts in plane 2
+pub fn set_graphics_mode() {
+    program(&GRAPHICS_320X200);
+}
+
+// 6 bits per channel
+pub fn read_dac(index: u8) -> [u8; 3] {
+    unsafe { Port::new(DAC_READ_ADDRESS).write(index) }
+    let mut data: Port<u8> = Port::new(DAC_DATA);
+    let mut rgb = [0; 3];
+    for channel in &mut rgb {
+        *channel = unsafe { data.read() };
+    }
+    rgb
+}
+
+pub fn write_dac(index: u8, rgb: [u8; 3]) {
+    unsafe { Port::new(DAC_WRITE_ADDRESS).write(index) }
+    let mut data: Port<u8> = Port::new(DAC_DATA);
+    for channel in rgb {
+        unsafe { data.write(channel) }
+    }
+}
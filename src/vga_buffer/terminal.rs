@@ -0,0 +1,472 @@
+use {
+    super::{
+        ANSI_COLORS, ATTRIBUTE_STACK_SIZE, Attributes, Color, CursorShape, ScreenChar, VGA_HISTORY,
+        VGA_MAX_HEIGHT, VGA_MAX_WIDTH,
+        ansi::{self, Action, Params, Parser},
+    },
+    alloc::{vec, vec::Vec},
+    core::{fmt, ops::Range},
+};
+
+// Everything a tty needs to be written to, whether it is displayed or not.
+// Nothing here touches the hardware, the compositor (Writer) pushes the changes.
+pub struct VirtualTerminal {
+    // Ring of lines of stride cells each: logical line 0 is the oldest one,
+    // VGA_HISTORY - 1 the one being written
+    lines: Vec<ScreenChar>,
+    // The widest the terminal has been, the columns hidden by a narrower size are kept
+    stride: usize,
+    head: usize,
+    dirty: [bool; VGA_HISTORY],
+    history: usize,
+    scroll_up: usize,
+    // Size of the display, minus the row taken by the status bar
+    cols: usize,
+    rows: usize,
+    // Visible row of the cursor, output sticks to the bottom unless moved by escape codes
+    row: usize,
+    col: usize,
+    cursor_shape: CursorShape,
+    saved_cursor: (usize, usize, Attributes),
+    attributes: Attributes,
+    attribute_stack: [Attributes; ATTRIBUTE_STACK_SIZE],
+    // Can exceed ATTRIBUTE_STACK_SIZE, pops then restore nothing until it fits again
+    attribute_depth: usize,
+    parser: Parser,
+    // Set when the lines moved, so selections made on the old positions are dropped
+    scrolled: bool,
+}
+
+impl VirtualTerminal {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        Self {
+            lines: vec![ScreenChar::black_space(); VGA_HISTORY * cols],
+            stride: cols,
+            head: 0,
+            dirty: [true; VGA_HISTORY],
+            history: 0,
+            scroll_up: 0,
+            cols,
+            rows,
+            row: rows - 1,
+            col: 0,
+            cursor_shape: CursorShape::Underline,
+            saved_cursor: (rows - 1, 0, Attributes::DEFAULT),
+            attributes: Attributes::DEFAULT,
+            attribute_stack: [Attributes::DEFAULT; ATTRIBUTE_STACK_SIZE],
+            attribute_depth: 0,
+            parser: Parser::new(),
+            scrolled: false,
+        }
+    }
+
+    pub const fn physical(&self, line: usize) -> usize {
+        let idx = self.head + line;
+        if idx >= VGA_HISTORY {
+            idx - VGA_HISTORY
+        } else {
+            idx
+        }
+    }
+
+    const fn cells(&self, idx: usize) -> Range<usize> {
+        idx * self.stride..(idx + 1) * self.stride
+    }
+
+    pub fn line(&self, line: usize) -> &[ScreenChar] {
+        &self.lines[self.cells(self.physical(line))]
+    }
+
+    const fn hidden_lines(&self) -> usize {
+        VGA_HISTORY - self.rows
+    }
+
+    // Logical line shown on a row of the display
+    pub const fn visible_line(&self, row: usize) -> usize {
+        row + self.hidden_lines() - self.scroll_up
+    }
+
+    pub const fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // Keeps the bottom line in place, lines leaving the top go to the history.
+    // Lines aren't rewrapped, the columns past the width are just hidden.
+    pub fn set_size(&mut self, cols: usize, rows: usize) {
+        if cols == 0 || cols > VGA_MAX_WIDTH || rows == 0 || rows > VGA_MAX_HEIGHT {
+            return;
+        }
+        if cols > self.stride {
+            self.widen(cols);
+        }
+        self.cols = cols;
+        if self.col > cols {
+            self.col = cols;
+        }
+        if rows < self.rows {
+            let removed = self.rows - rows;
+            self.row = self.row.saturating_sub(removed);
+            self.saved_cursor.0 = self.saved_cursor.0.saturating_sub(removed);
+            self.history = if self.history + removed < VGA_HISTORY - rows {
+                self.history + removed
+            } else {
+                VGA_HISTORY - rows
+            };
+        } else {
+            let added = rows - self.rows;
+            self.row += added;
+            self.saved_cursor.0 += added;
+            self.history = self.history.saturating_sub(added);
+            if self.scroll_up > self.history {
+                self.scroll_up = self.history;
+            }
+        }
+        self.rows = rows;
+        self.scrolled = true;
+    }
+
+    fn widen(&mut self, stride: usize) {
+        let mut lines = vec![ScreenChar::black_space(); VGA_HISTORY * stride];
+        for (new, old) in lines
+            .chunks_exact_mut(stride)
+            .zip(self.lines.chunks_exact(self.stride))
+        {
+            new[..old.len()].copy_from_slice(old);
+        }
+        self.lines = lines;
+        self.stride = stride;
+        self.dirty = [true; VGA_HISTORY];
+    }
+
+    pub const fn take_dirty(&mut self, idx: usize) -> bool {
+        let dirty = self.dirty[idx];
+        self.dirty[idx] = false;
+        dirty
+    }
+
+    pub const fn take_scrolled(&mut self) -> bool {
+        let scrolled = self.scrolled;
+        self.scrolled = false;
+        scrolled
+    }
+
+    // None while looking at the history
+    pub const fn cursor(&self) -> Option<(usize, usize)> {
+        if self.scroll_up == 0 {
+            Some((self.row, self.col))
+        } else {
+            None
+        }
+    }
+
+    pub const fn cursor_shape(&self) -> CursorShape {
+        self.cursor_shape
+    }
+
+    pub const fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.cursor_shape = shape;
+    }
+
+    const fn cursor_line(&self) -> usize {
+        self.hidden_lines() + self.row
+    }
+
+    // Rewriting the same character doesn't make the line dirty
+    fn set_char(&mut self, line: usize, col: usize, sc: ScreenChar) {
+        let idx = self.physical(line);
+        let cell = &mut self.lines[idx * self.stride + col];
+        if *cell != sc {
+            *cell = sc;
+            self.dirty[idx] = true;
+        }
+    }
+
+    const fn blank(&self) -> ScreenChar {
+        ScreenChar {
+            ascii_character: b' ',
+            color_code: self.attributes.color_code(),
+        }
+    }
+
+    pub fn write_byte(&mut self, byte: u8) {
+        self.put_byte(byte);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.put_byte(byte);
+        }
+    }
+
+    pub fn write_repeated(&mut self, byte: u8, count: usize) {
+        for _ in 0..count {
+            self.put_byte(byte);
+        }
+    }
+
+    // Writes code page 437 glyphs as is, without interpreting control characters
+    pub fn write_glyphs(&mut self, glyphs: &[u8]) {
+        for &glyph in glyphs {
+            self.put_glyph(glyph);
+        }
+    }
+
+    fn put_byte(&mut self, byte: u8) {
+        match self.parser.advance(byte) {
+            Some(Action::Print(glyph)) => self.put_glyph(glyph),
+            Some(Action::Execute(control)) => self.execute(control),
+            Some(Action::Escape(action)) => self.escape(action),
+            Some(Action::ControlSequence {
+                private: false,
+                params,
+                action,
+            }) => self.control_sequence(&params, action),
+            _ => {}
+        }
+    }
+
+    fn put_glyph(&mut self, glyph: u8) {
+        if self.col >= self.cols {
+            self.new_line();
+        }
+        let sc = ScreenChar {
+            ascii_character: glyph,
+            color_code: self.attributes.color_code(),
+        };
+        self.set_char(self.cursor_line(), self.col, sc);
+        self.col += 1;
+    }
+
+    fn execute(&mut self, control: u8) {
+        match control {
+            b'\n' => self.new_line(),
+            b'\r' => self.col = 0,
+            b'\t' => {
+                let next_tab_stop = (self.col | 7) + 1;
+                self.col = if next_tab_stop < self.cols {
+                    next_tab_stop
+                } else {
+                    self.cols - 1
+                };
+            }
+            0x08 => self.col = self.col.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn escape(&mut self, action: u8) {
+        match action {
+            b'7' => self.save_cursor(),
+            b'8' => self.restore_cursor(),
+            b'c' => {
+                self.attributes = Attributes::DEFAULT;
+                self.clear();
+                self.move_cursor(0, 0);
+            }
+            _ => {}
+        }
+    }
+
+    fn control_sequence(&mut self, params: &Params, action: u8) {
+        let row = self.row;
+        let col = self.col.min(self.cols - 1);
+        let n = usize::from(params.get_or(0, 1));
+        match action {
+            b'A' => self.move_cursor(row.saturating_sub(n), col),
+            b'B' => self.move_cursor(row + n, col),
+            b'C' => self.move_cursor(row, col + n),
+            b'D' => self.move_cursor(row, col.saturating_sub(n)),
+            b'G' => self.move_cursor(row, n - 1),
+            b'H' | b'f' => self.move_cursor(n - 1, usize::from(params.get_or(1, 1)) - 1),
+            b'J' => self.erase_in_display(params.get_or(0, 0)),
+            b'K' => self.erase_in_line(params.get_or(0, 0)),
+            b'm' => self.select_graphic_rendition(params),
+            b's' => self.save_cursor(),
+            b'u' => self.restore_cursor(),
+            _ => {}
+        }
+    }
+
+    const fn move_cursor(&mut self, row: usize, col: usize) {
+        self.row = if row < self.rows { row } else { self.rows - 1 };
+        self.col = if col < self.cols { col } else { self.cols - 1 };
+    }
+
+    const fn save_cursor(&mut self) {
+        self.saved_cursor = (self.row, self.col, self.attributes);
+    }
+
+    const fn restore_cursor(&mut self) {
+        let (row, col, attributes) = self.saved_cursor;
+        self.attributes = attributes;
+        self.move_cursor(row, col);
+    }
+
+    fn erase_line(&mut self, row: usize, from: usize, to: usize) {
+        let blank = self.blank();
+        for col in from..to {
+            self.set_char(self.hidden_lines() + row, col, blank);
+        }
+    }
+
+    fn erase_in_line(&mut self, mode: u16) {
+        let row = self.row;
+        let col = self.col.min(self.cols - 1);
+        match mode {
+            0 => self.erase_line(row, col, self.cols),
+            1 => self.erase_line(row, 0, col + 1),
+            2 => self.erase_line(row, 0, self.cols),
+            _ => {}
+        }
+    }
+
+    fn erase_in_display(&mut self, mode: u16) {
+        let row = self.row;
+        match mode {
+            0 => {
+                self.erase_in_line(0);
+                for below in row + 1..self.rows {
+                    self.erase_line(below, 0, self.cols);
+                }
+            }
+            1 => {
+                self.erase_in_line(1);
+                for above in 0..row {
+                    self.erase_line(above, 0, self.cols);
+                }
+            }
+            2 => {
+                for y in 0..self.rows {
+                    self.erase_line(y, 0, self.cols);
+                }
+            }
+            3 => self.clear(),
+            _ => {}
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, params: &Params) {
+        if params.as_slice().is_empty() {
+            self.attributes = Attributes::DEFAULT;
+        }
+        for &param in params.as_slice() {
+            match param {
+                0 => self.attributes = Attributes::DEFAULT,
+                1 => self.attributes.bold = true,
+                22 => self.attributes.bold = false,
+                5 => self.attributes.blink = true,
+                25 => self.attributes.blink = false,
+                7 => self.attributes.reverse = true,
+                27 => self.attributes.reverse = false,
+                30..=37 => self.attributes.foreground = ANSI_COLORS[usize::from(param - 30)],
+                39 => self.attributes.foreground = Attributes::DEFAULT.foreground,
+                40..=47 => self.attributes.background = ANSI_COLORS[usize::from(param - 40)],
+                49 => self.attributes.background = Attributes::DEFAULT.background,
+                90..=97 => self.attributes.foreground = ANSI_COLORS[usize::from(param - 82)],
+                100..=107 => self.attributes.background = ANSI_COLORS[usize::from(param - 92)],
+                _ => {}
+            }
+        }
+    }
+
+    pub const fn set_foreground_color(&mut self, foreground: Color) {
+        self.attributes.foreground = foreground;
+    }
+
+    pub const fn set_background_color(&mut self, background: Color) {
+        self.attributes.background = background;
+    }
+
+    pub const fn push_attributes(&mut self) {
+        if self.attribute_depth < ATTRIBUTE_STACK_SIZE {
+            self.attribute_stack[self.attribute_depth] = self.attributes;
+        }
+        self.attribute_depth += 1;
+    }
+
+    pub const fn pop_attributes(&mut self) {
+        if self.attribute_depth > 0 {
+            self.attribute_depth -= 1;
+            if self.attribute_depth < ATTRIBUTE_STACK_SIZE {
+                self.attributes = self.attribute_stack[self.attribute_depth];
+            }
+        }
+    }
+
+    pub fn with_foreground_color<F>(&mut self, foreground: Color, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.push_attributes();
+        self.set_foreground_color(foreground);
+        f(self);
+        self.pop_attributes();
+    }
+
+    pub const fn set_cursor(&mut self, col: usize) {
+        self.col = col;
+    }
+
+    pub fn clear(&mut self) {
+        self.history = 0;
+        self.scroll_up = 0;
+        self.lines.fill(ScreenChar::white_space());
+        self.dirty = [true; VGA_HISTORY];
+        self.head = 0;
+        self.scrolled = true;
+    }
+
+    pub const fn move_up(&mut self) {
+        if self.scroll_up < self.history {
+            self.scroll_up += 1;
+        }
+    }
+
+    pub const fn move_down(&mut self) {
+        if self.scroll_up > 0 {
+            self.scroll_up -= 1;
+        }
+    }
+
+    pub const fn move_all_the_way_up(&mut self) {
+        self.scroll_up = self.history;
+    }
+
+    pub const fn move_all_the_way_down(&mut self) {
+        self.scroll_up = 0;
+    }
+
+    pub const fn reset_history(&mut self) {
+        self.history = 0;
+    }
+
+    fn new_line(&mut self) {
+        self.col = 0;
+        if self.row < self.rows - 1 {
+            self.row += 1;
+            return;
+        }
+        let idx = self.head;
+        self.head = self.physical(1);
+        let blank = self.blank();
+        let cells = self.cells(idx);
+        self.lines[cells].fill(blank);
+        self.dirty[idx] = true;
+        if self.history < self.hidden_lines() {
+            self.history += 1;
+        }
+        self.scrolled = true;
+    }
+}
+
+impl fmt::Write for VirtualTerminal {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            match byte {
+                0x20..=0x7e | b'\n' | b'\r' | b'\t' | ansi::ESCAPE => self.put_byte(byte),
+                _ => self.put_byte(0xfe),
+            }
+        }
+        Ok(())
+    }
+}